@@ -0,0 +1,36 @@
+//! Runtime API definition for the SerpMarket module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+// The `unnecessary_mut_passed` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+pub use serp_market::{AccountBalance, SupplyAdjustmentRecord};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait SerpMarketApi<AccountId, CurrencyId, BlockNumber, Balance> where
+		AccountId: Codec,
+		CurrencyId: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+	{
+		/// The past supply adjustments of `currency_id`, oldest first.
+		fn supply_history(currency_id: CurrencyId) -> Vec<SupplyAdjustmentRecord<BlockNumber, Balance>>;
+
+		/// The free balance of `who` in `currency_id`.
+		fn free_balance(currency_id: CurrencyId, who: AccountId) -> Balance;
+
+		/// The reserved balance of `who` in `currency_id`.
+		fn reserved_balance(currency_id: CurrencyId, who: AccountId) -> Balance;
+
+		/// The total issuance of `currency_id`.
+		fn total_issuance(currency_id: CurrencyId) -> Balance;
+
+		/// The balances of `who` in the native currency and every registered
+		/// stable currency.
+		fn all_balances(who: AccountId) -> Vec<(CurrencyId, AccountBalance<Balance>)>;
+	}
+}
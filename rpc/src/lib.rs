@@ -0,0 +1,139 @@
+//! RPC interface for the SerpMarket module.
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::{fmt::Debug, sync::Arc};
+
+pub use self::gen_client::Client as SerpMarketClient;
+pub use serp_market_rpc_runtime_api::{AccountBalance, SerpMarketApi as SerpMarketRuntimeApi, SupplyAdjustmentRecord};
+
+#[rpc]
+pub trait SerpMarketApi<BlockHash, AccountId, CurrencyId, BlockNumber, Balance> {
+	#[rpc(name = "serpMarket_supplyHistory")]
+	fn supply_history(
+		&self,
+		currency_id: CurrencyId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<SupplyAdjustmentRecord<BlockNumber, Balance>>>;
+
+	#[rpc(name = "serpMarket_freeBalance")]
+	fn free_balance(&self, currency_id: CurrencyId, who: AccountId, at: Option<BlockHash>) -> Result<Balance>;
+
+	#[rpc(name = "serpMarket_reservedBalance")]
+	fn reserved_balance(&self, currency_id: CurrencyId, who: AccountId, at: Option<BlockHash>) -> Result<Balance>;
+
+	#[rpc(name = "serpMarket_totalIssuance")]
+	fn total_issuance(&self, currency_id: CurrencyId, at: Option<BlockHash>) -> Result<Balance>;
+
+	#[rpc(name = "serpMarket_allBalances")]
+	fn all_balances(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<(CurrencyId, AccountBalance<Balance>)>>;
+}
+
+/// A struct that implements the [`SerpMarketApi`].
+pub struct SerpMarket<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> SerpMarket<C, B> {
+	/// Create new `SerpMarket` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		SerpMarket {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+pub enum Error {
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(message: &str, e: impl Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, CurrencyId, BlockNumber, Balance>
+	SerpMarketApi<<Block as BlockT>::Hash, AccountId, CurrencyId, BlockNumber, Balance> for SerpMarket<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SerpMarketRuntimeApi<Block, AccountId, CurrencyId, BlockNumber, Balance>,
+	AccountId: Codec,
+	CurrencyId: Codec,
+	BlockNumber: Codec,
+	Balance: Codec,
+{
+	fn supply_history(
+		&self,
+		currency_id: CurrencyId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<SupplyAdjustmentRecord<BlockNumber, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.supply_history(&at, currency_id)
+			.map_err(|e| runtime_error("Unable to get supply history.", e))
+	}
+
+	fn free_balance(
+		&self,
+		currency_id: CurrencyId,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.free_balance(&at, currency_id, who)
+			.map_err(|e| runtime_error("Unable to get free balance.", e))
+	}
+
+	fn reserved_balance(
+		&self,
+		currency_id: CurrencyId,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.reserved_balance(&at, currency_id, who)
+			.map_err(|e| runtime_error("Unable to get reserved balance.", e))
+	}
+
+	fn total_issuance(&self, currency_id: CurrencyId, at: Option<<Block as BlockT>::Hash>) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.total_issuance(&at, currency_id)
+			.map_err(|e| runtime_error("Unable to get total issuance.", e))
+	}
+
+	fn all_balances(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<(CurrencyId, AccountBalance<Balance>)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.all_balances(&at, who)
+			.map_err(|e| runtime_error("Unable to get all balances.", e))
+	}
+}
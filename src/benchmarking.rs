@@ -0,0 +1,927 @@
+//! Benchmarks for the SerpMarket module.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::One;
+
+const SEED: u32 = 0;
+
+pub struct Module<T: Config>(crate::Pallet<T>);
+
+/// The runtime setup the benchmarks need beyond `crate::Config`.
+pub trait Config: crate::Config {
+	/// A registered stable currency the benchmarks can serp.
+	fn stable_currency_id() -> CurrencyIdOf<Self>;
+
+	/// The registered stable currencies `on_initialize` can serp.
+	fn stable_currency_ids() -> Vec<CurrencyIdOf<Self>>;
+
+	/// Make `PriceProvider` quote `price` for `currency_id`.
+	fn set_price(currency_id: CurrencyIdOf<Self>, price: BalanceOf<Self>);
+
+	/// Make `contract_by` of `currency_id` available for `contract_supply`
+	/// to contract.
+	fn setup_contract_supply(currency_id: CurrencyIdOf<Self>, contract_by: BalanceOf<Self>);
+}
+
+/// A thousand base units of `currency_id`.
+fn units<T: Config>(currency_id: CurrencyIdOf<T>) -> BalanceOf<T> {
+	<crate::Pallet<T> as Stp258Currency<T::AccountId>>::base_unit(currency_id)
+		.max(<crate::Pallet<T> as Stp258Currency<T::AccountId>>::minimum_balance(currency_id))
+		.max(One::one())
+		.saturating_mul(1_000u32.into())
+}
+
+/// Enable `currency_id` with caps high enough for `amount` to be serped.
+fn enable_stable_currency<T: Config>(currency_id: CurrencyIdOf<T>, amount: BalanceOf<T>) {
+	StableCurrencies::<T>::insert(
+		currency_id,
+		StableCurrencyInfo {
+			peg: units::<T>(currency_id),
+			base_unit: units::<T>(currency_id),
+			enabled: true,
+			max_expansion: amount,
+		},
+	);
+	SupplyCapsOf::<T>::remove(currency_id);
+}
+
+/// Seed the pool of `currency_id` with `native_amount` of native currency
+/// and `stable_amount` of `currency_id` from a fresh provider.
+fn seed_pool<T: Config>(
+	currency_id: CurrencyIdOf<T>,
+	native_amount: BalanceOf<T>,
+	stable_amount: BalanceOf<T>,
+) -> DispatchResult {
+	let provider: T::AccountId = account("provider", 0, SEED);
+	<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(T::GetStp258NativeId::get(), &provider, native_amount)?;
+	<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &provider, stable_amount)?;
+	crate::Pallet::<T>::add_liquidity(
+		RawOrigin::Signed(provider).into(),
+		currency_id,
+		native_amount,
+		stable_amount,
+		Zero::zero(),
+	)
+	.map(|_| ())
+	.map_err(|e| e.error)
+}
+
+/// Place `count` buy orders of `amount` at `price` on the book of
+/// `currency_id`, each by its own trader.
+fn fill_book<T: Config>(
+	currency_id: CurrencyIdOf<T>,
+	count: u32,
+	price: BalanceOf<T>,
+	amount: BalanceOf<T>,
+) -> DispatchResult {
+	let cost = crate::Pallet::<T>::order_cost(amount, price)?;
+	for i in 0..count {
+		let trader: T::AccountId = account("trader", i, SEED);
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &trader, cost)?;
+		crate::Pallet::<T>::place_order(RawOrigin::Signed(trader).into(), currency_id, OrderSide::Buy, price, amount)
+			.map_err(|e| e.error)?;
+	}
+	Ok(())
+}
+
+benchmarks! {
+	transfer_non_native_currency {
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		let from: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &from, amount)?;
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: transfer(RawOrigin::Signed(from), to_lookup, currency_id, amount)
+	verify {
+		assert_eq!(<crate::Pallet<T> as Stp258Currency<T::AccountId>>::free_balance(currency_id, &to), amount);
+	}
+
+	transfer_native_currency {
+		let native_currency_id = T::GetStp258NativeId::get();
+		let amount = units::<T>(native_currency_id);
+		let from: T::AccountId = whitelisted_caller();
+		T::Stp258Native::deposit(&from, amount)?;
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Signed(from), to_lookup, amount)
+	verify {
+		assert_eq!(T::Stp258Native::free_balance(&to), amount);
+	}
+
+	transfer_all_non_native_currency {
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		let from: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &from, amount)?;
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: transfer_all(RawOrigin::Signed(from), to_lookup, currency_id)
+	verify {
+		assert_eq!(<crate::Pallet<T> as Stp258Currency<T::AccountId>>::free_balance(currency_id, &to), amount);
+	}
+
+	transfer_all_native_currency {
+		let native_currency_id = T::GetStp258NativeId::get();
+		let amount = units::<T>(native_currency_id);
+		let from: T::AccountId = whitelisted_caller();
+		T::Stp258Native::deposit(&from, amount)?;
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: transfer_all(RawOrigin::Signed(from), to_lookup, native_currency_id)
+	verify {
+		assert_eq!(T::Stp258Native::free_balance(&to), amount);
+	}
+
+	transfer_keep_alive_non_native_currency {
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		let from: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &from, amount.saturating_mul(2u32.into()))?;
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: transfer_keep_alive(RawOrigin::Signed(from), to_lookup, currency_id, amount)
+	verify {
+		assert_eq!(<crate::Pallet<T> as Stp258Currency<T::AccountId>>::free_balance(currency_id, &to), amount);
+	}
+
+	transfer_keep_alive_native_currency {
+		let native_currency_id = T::GetStp258NativeId::get();
+		let amount = units::<T>(native_currency_id);
+		let from: T::AccountId = whitelisted_caller();
+		T::Stp258Native::deposit(&from, amount.saturating_mul(2u32.into()))?;
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: transfer_keep_alive(RawOrigin::Signed(from), to_lookup, native_currency_id, amount)
+	verify {
+		assert_eq!(T::Stp258Native::free_balance(&to), amount);
+	}
+
+	transfer_batch {
+		let c in 1 .. T::MaxTransferBatchSize::get();
+
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		let from: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &from, amount.saturating_mul(c.into()))?;
+
+		let transfers = (0..c)
+			.map(|i| (T::Lookup::unlookup(account("to", i, SEED)), currency_id, amount))
+			.collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(from.clone()), transfers)
+	verify {
+		assert_eq!(<crate::Pallet<T> as Stp258Currency<T::AccountId>>::free_balance(currency_id, &from), Zero::zero());
+	}
+
+	on_initialize {
+		let c in 0 .. T::stable_currency_ids().len() as u32;
+		let now = T::AdjustmentFrequency::get();
+		let serped = T::stable_currency_ids().into_iter().take(c as usize).collect::<Vec<_>>();
+		for (currency_id, _) in StableCurrencies::<T>::iter().collect::<Vec<_>>() {
+			StableCurrencies::<T>::remove(currency_id);
+		}
+		let who: T::AccountId = account("who", 0, SEED);
+		for currency_id in serped.iter() {
+			let peg = units::<T>(*currency_id);
+			<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(*currency_id, &who, peg)?;
+			let total_issuance = <crate::Pallet<T> as Stp258Currency<T::AccountId>>::total_issuance(*currency_id);
+			enable_stable_currency::<T>(*currency_id, total_issuance);
+			T::set_price(*currency_id, peg.saturating_add(peg / 10u32.into()));
+		}
+		let total_issuances = serped
+			.iter()
+			.map(|currency_id| <crate::Pallet<T> as Stp258Currency<T::AccountId>>::total_issuance(*currency_id))
+			.collect::<Vec<_>>();
+	}: {
+		crate::Pallet::<T>::serp_stable_currencies(now);
+	}
+	verify {
+		for (currency_id, total_issuance) in serped.iter().zip(total_issuances) {
+			assert!(<crate::Pallet<T> as Stp258Currency<T::AccountId>>::total_issuance(*currency_id) > total_issuance);
+		}
+	}
+
+	lift_expired_locks {
+		let l in 0 .. T::MaxLockExpiries::get();
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		frame_system::Module::<T>::set_block_number(Zero::zero());
+		for i in 0..l {
+			let who: T::AccountId = account("who", i, SEED);
+			<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &who, amount)?;
+			<crate::Pallet<T> as Stp258CurrencyTimedLockable<T::AccountId>>::set_lock_until(
+				*b"bench   ",
+				currency_id,
+				&who,
+				amount,
+				One::one(),
+			)?;
+		}
+	}: {
+		crate::Pallet::<T>::lift_expired_locks(One::one());
+	}
+	verify {
+		assert!(ExpiringLocks::<T>::get(T::BlockNumber::one()).is_empty());
+	}
+
+	merge_account {
+		let currency_id = T::stable_currency_id();
+		let native_currency_id = T::GetStp258NativeId::get();
+		let source: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &source, units::<T>(currency_id))?;
+		T::Stp258Native::deposit(&source, units::<T>(native_currency_id))?;
+
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let dest_lookup = T::Lookup::unlookup(dest.clone());
+	}: _(RawOrigin::Signed(source), dest_lookup)
+	verify {
+		assert_eq!(<crate::Pallet<T> as Stp258Currency<T::AccountId>>::free_balance(currency_id, &dest), units::<T>(currency_id));
+		assert_eq!(T::Stp258Native::free_balance(&dest), units::<T>(native_currency_id));
+	}
+
+	update_balance_non_native_currency {
+		let origin = T::UpdateOrigin::successful_origin();
+		let currency_id = T::stable_currency_id();
+		let balance = units::<T>(currency_id);
+		let amount = AmountOf::<T>::try_from(balance).map_err(|_| "balance does not fit in amount")?;
+		let who: T::AccountId = account("who", 0, SEED);
+		let who_lookup = T::Lookup::unlookup(who.clone());
+	}: update_balance<T::Origin>(origin, who_lookup, currency_id, amount)
+	verify {
+		assert_eq!(<crate::Pallet<T> as Stp258Currency<T::AccountId>>::free_balance(currency_id, &who), balance);
+	}
+
+	update_balance_native_currency_creating {
+		let origin = T::UpdateOrigin::successful_origin();
+		let native_currency_id = T::GetStp258NativeId::get();
+		let balance = units::<T>(native_currency_id);
+		let amount = AmountOf::<T>::try_from(balance).map_err(|_| "balance does not fit in amount")?;
+		let who: T::AccountId = account("who", 0, SEED);
+		let who_lookup = T::Lookup::unlookup(who.clone());
+	}: update_balance<T::Origin>(origin, who_lookup, native_currency_id, amount)
+	verify {
+		assert_eq!(T::Stp258Native::free_balance(&who), balance);
+	}
+
+	update_balance_native_currency_killing {
+		let origin = T::UpdateOrigin::successful_origin();
+		let native_currency_id = T::GetStp258NativeId::get();
+		let balance = units::<T>(native_currency_id);
+		let amount = AmountOf::<T>::try_from(balance).map_err(|_| "balance does not fit in amount")?;
+		let who: T::AccountId = account("who", 0, SEED);
+		let who_lookup = T::Lookup::unlookup(who.clone());
+		T::Stp258Native::deposit(&who, balance)?;
+	}: update_balance<T::Origin>(origin, who_lookup, native_currency_id, -amount)
+	verify {
+		assert_eq!(T::Stp258Native::free_balance(&who), Zero::zero());
+	}
+
+	register_stable_currency {
+		let origin = T::UpdateOrigin::successful_origin();
+		let currency_id = T::stable_currency_id();
+		let info = StableCurrencies::<T>::take(currency_id).unwrap_or_default();
+	}: _<T::Origin>(origin, currency_id, info)
+	verify {
+		assert_eq!(crate::Pallet::<T>::stable_currencies(currency_id), Some(info));
+	}
+
+	update_stable_currency {
+		let origin = T::UpdateOrigin::successful_origin();
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		enable_stable_currency::<T>(currency_id, amount);
+		let info = StableCurrencyInfo {
+			enabled: false,
+			..crate::Pallet::<T>::stable_currencies(currency_id).unwrap_or_default()
+		};
+	}: _<T::Origin>(origin, currency_id, info)
+	verify {
+		assert_eq!(crate::Pallet::<T>::stable_currencies(currency_id), Some(info));
+	}
+
+	deregister_stable_currency {
+		let origin = T::UpdateOrigin::successful_origin();
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		enable_stable_currency::<T>(currency_id, amount);
+	}: _<T::Origin>(origin, currency_id)
+	verify {
+		assert_eq!(crate::Pallet::<T>::stable_currencies(currency_id), None);
+	}
+
+	set_supply_caps {
+		let origin = T::UpdateOrigin::successful_origin();
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		enable_stable_currency::<T>(currency_id, amount);
+		let cap = SupplyCap {
+			absolute: amount,
+			ratio: Perbill::from_percent(100),
+		};
+		let caps = SupplyCaps {
+			per_call: cap,
+			per_block: cap,
+			per_window: cap,
+		};
+	}: _<T::Origin>(origin, currency_id, Some(caps))
+	verify {
+		assert_eq!(crate::Pallet::<T>::supply_caps(currency_id), Some(caps));
+	}
+
+	pause {
+		let origin = T::PauseOrigin::successful_origin();
+		let currency_id = T::stable_currency_id();
+	}: _<T::Origin>(origin, currency_id)
+	verify {
+		assert!(crate::Pallet::<T>::paused_currencies(currency_id));
+	}
+
+	unpause {
+		let origin = T::PauseOrigin::successful_origin();
+		let currency_id = T::stable_currency_id();
+		PausedCurrencies::<T>::insert(currency_id, true);
+	}: _<T::Origin>(origin, currency_id)
+	verify {
+		assert!(!crate::Pallet::<T>::paused_currencies(currency_id));
+	}
+
+	pause_all {
+		let origin = T::PauseOrigin::successful_origin();
+	}: _<T::Origin>(origin)
+	verify {
+		assert!(crate::Pallet::<T>::market_paused());
+	}
+
+	unpause_all {
+		let origin = T::PauseOrigin::successful_origin();
+		MarketPaused::<T>::put(true);
+	}: _<T::Origin>(origin)
+	verify {
+		assert!(!crate::Pallet::<T>::market_paused());
+	}
+
+	expand_supply {
+		let currency_id = T::stable_currency_id();
+		let expand_by = units::<T>(currency_id);
+		enable_stable_currency::<T>(currency_id, expand_by);
+		let total_issuance = <crate::Pallet<T> as Stp258Currency<T::AccountId>>::total_issuance(currency_id);
+	}: {
+		<crate::Pallet<T> as SerpMarket<T::AccountId>>::expand_supply(
+			T::GetStp258NativeId::get(),
+			currency_id,
+			expand_by,
+			units::<T>(currency_id),
+		)?;
+	}
+	verify {
+		assert_eq!(
+			<crate::Pallet<T> as Stp258Currency<T::AccountId>>::total_issuance(currency_id),
+			total_issuance + expand_by
+		);
+	}
+
+	contract_supply {
+		let currency_id = T::stable_currency_id();
+		let contract_by = units::<T>(currency_id);
+		enable_stable_currency::<T>(currency_id, contract_by);
+		T::setup_contract_supply(currency_id, contract_by);
+		let total_issuance = <crate::Pallet<T> as Stp258Currency<T::AccountId>>::total_issuance(currency_id);
+	}: {
+		<crate::Pallet<T> as SerpMarket<T::AccountId>>::contract_supply(
+			T::GetStp258NativeId::get(),
+			currency_id,
+			contract_by,
+			units::<T>(currency_id),
+		)?;
+	}
+	verify {
+		assert_eq!(
+			<crate::Pallet<T> as Stp258Currency<T::AccountId>>::total_issuance(currency_id),
+			total_issuance - contract_by
+		);
+	}
+
+	claim {
+		let s in 1 .. T::MaxVestingSchedules::get();
+
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &who, amount.saturating_mul(s.into()))?;
+		for _ in 0..s {
+			crate::Pallet::<T>::add_vesting_schedule(
+				&who,
+				currency_id,
+				VestingSchedule {
+					start: Zero::zero(),
+					cliff: Zero::zero(),
+					duration: 10u32.into(),
+					total: amount,
+				},
+			)?;
+		}
+		frame_system::Module::<T>::set_block_number(10u32.into());
+	}: _(RawOrigin::Signed(who.clone()), currency_id)
+	verify {
+		assert!(crate::Pallet::<T>::vesting_schedules(&who, currency_id).is_empty());
+	}
+
+	bid {
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &who, amount)?;
+		Auctions::<T>::insert(
+			currency_id,
+			ContractionAuction {
+				start: Zero::zero(),
+				end: 10u32.into(),
+				target: amount,
+				raised: Zero::zero(),
+				start_price: amount,
+				floor_price: One::one(),
+				quote_price: amount,
+			},
+		);
+	}: _(RawOrigin::Signed(who.clone()), currency_id, amount)
+	verify {
+		assert_eq!(crate::Pallet::<T>::auction_bids(currency_id), vec![(who, amount)]);
+	}
+
+	settle_auction {
+		let b in 1 .. T::MaxAuctionBids::get();
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		let auction = ContractionAuction {
+			start: Zero::zero(),
+			end: 10u32.into(),
+			target: amount.saturating_mul(b.into()),
+			raised: Zero::zero(),
+			start_price: amount,
+			floor_price: One::one(),
+			quote_price: amount,
+		};
+		Auctions::<T>::insert(currency_id, auction);
+		AuctionCount::<T>::put(1);
+		for i in 0..b {
+			let bidder: T::AccountId = account("bidder", i, SEED);
+			<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &bidder, amount)?;
+			crate::Pallet::<T>::bid(RawOrigin::Signed(bidder).into(), currency_id, amount)?;
+		}
+	}: {
+		crate::Pallet::<T>::settle_auction(currency_id, auction, Zero::zero());
+	}
+	verify {
+		assert_eq!(crate::Pallet::<T>::auctions(currency_id), None);
+		assert_eq!(crate::Pallet::<T>::auction_count(), 0);
+	}
+
+	buy_bonds {
+		let currency_id = T::stable_currency_id();
+		let amount = units::<T>(currency_id);
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &who, amount)?;
+		BondOffers::<T>::insert(currency_id, amount);
+	}: _(RawOrigin::Signed(who.clone()), currency_id, amount)
+	verify {
+		assert_eq!(crate::Pallet::<T>::bonds(currency_id, 0).map(|bond| bond.owner), Some(who));
+	}
+
+	set_seigniorage_shares {
+		let origin = T::UpdateOrigin::successful_origin();
+		let shares = SeigniorageShares {
+			treasury: Perbill::from_percent(25),
+			serpers: Perbill::from_percent(25),
+			stakers: Perbill::from_percent(25),
+			reserve_buffer: Perbill::from_percent(25),
+		};
+	}: _<T::Origin>(origin, Some(shares))
+	verify {
+		assert_eq!(crate::Pallet::<T>::seigniorage_shares(), Some(shares));
+	}
+
+	add_liquidity {
+		let currency_id = T::stable_currency_id();
+		let native_currency_id = T::GetStp258NativeId::get();
+		let native_amount = units::<T>(native_currency_id);
+		let stable_amount = units::<T>(currency_id);
+		seed_pool::<T>(currency_id, native_amount, stable_amount)?;
+
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(native_currency_id, &who, native_amount)?;
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &who, stable_amount)?;
+	}: _(RawOrigin::Signed(who.clone()), currency_id, native_amount, stable_amount, Zero::zero())
+	verify {
+		assert_eq!(crate::Pallet::<T>::liquidity_shares((native_currency_id, currency_id), &who), native_amount);
+	}
+
+	remove_liquidity {
+		let currency_id = T::stable_currency_id();
+		let native_currency_id = T::GetStp258NativeId::get();
+		let native_amount = units::<T>(native_currency_id);
+		let stable_amount = units::<T>(currency_id);
+		seed_pool::<T>(currency_id, native_amount, stable_amount)?;
+
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(native_currency_id, &who, native_amount)?;
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &who, stable_amount)?;
+		crate::Pallet::<T>::add_liquidity(
+			RawOrigin::Signed(who.clone()).into(),
+			currency_id,
+			native_amount,
+			stable_amount,
+			Zero::zero(),
+		)?;
+	}: _(RawOrigin::Signed(who.clone()), currency_id, native_amount, Zero::zero(), Zero::zero())
+	verify {
+		assert!(crate::Pallet::<T>::liquidity_shares((native_currency_id, currency_id), &who).is_zero());
+	}
+
+	swap_exact_in {
+		let currency_id = T::stable_currency_id();
+		let native_currency_id = T::GetStp258NativeId::get();
+		let native_amount = units::<T>(native_currency_id);
+		let stable_amount = units::<T>(currency_id);
+		seed_pool::<T>(currency_id, native_amount, stable_amount)?;
+
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &who, stable_amount)?;
+	}: _(RawOrigin::Signed(who.clone()), currency_id, native_currency_id, stable_amount, Zero::zero())
+	verify {
+		assert!(!<crate::Pallet<T> as Stp258Currency<T::AccountId>>::free_balance(native_currency_id, &who).is_zero());
+	}
+
+	swap_exact_out {
+		let currency_id = T::stable_currency_id();
+		let native_currency_id = T::GetStp258NativeId::get();
+		let native_amount = units::<T>(native_currency_id);
+		let stable_amount = units::<T>(currency_id);
+		seed_pool::<T>(currency_id, native_amount, stable_amount)?;
+
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, &who, stable_amount)?;
+		let amount_out = native_amount / 4u32.into();
+	}: _(RawOrigin::Signed(who.clone()), currency_id, native_currency_id, amount_out, stable_amount)
+	verify {
+		assert_eq!(
+			<crate::Pallet<T> as Stp258Currency<T::AccountId>>::free_balance(native_currency_id, &who),
+			amount_out
+		);
+	}
+
+	place_order {
+		let o in 0 .. T::MaxBookOrders::get().saturating_sub(T::MaxOpenOrders::get());
+		let currency_id = T::stable_currency_id();
+		let price = units::<T>(currency_id);
+		let amount = units::<T>(T::GetStp258NativeId::get());
+		let cost = crate::Pallet::<T>::order_cost(amount, price)?;
+		fill_book::<T>(currency_id, o, price, amount)?;
+
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(
+			currency_id,
+			&who,
+			cost.saturating_mul(T::MaxOpenOrders::get().into()),
+		)?;
+		for _ in 1..T::MaxOpenOrders::get() {
+			crate::Pallet::<T>::place_order(
+				RawOrigin::Signed(who.clone()).into(),
+				currency_id,
+				OrderSide::Buy,
+				price,
+				amount,
+			)?;
+		}
+	}: _(RawOrigin::Signed(who.clone()), currency_id, OrderSide::Buy, price, amount)
+	verify {
+		assert_eq!(crate::Pallet::<T>::open_orders(&who).len() as u32, T::MaxOpenOrders::get());
+	}
+
+	cancel_order {
+		let o in 0 .. T::MaxBookOrders::get() - 1;
+		let currency_id = T::stable_currency_id();
+		let price = units::<T>(currency_id);
+		let amount = units::<T>(T::GetStp258NativeId::get());
+		fill_book::<T>(currency_id, o, price, amount)?;
+
+		let who: T::AccountId = whitelisted_caller();
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(
+			currency_id,
+			&who,
+			crate::Pallet::<T>::order_cost(amount, price)?,
+		)?;
+		crate::Pallet::<T>::place_order(
+			RawOrigin::Signed(who.clone()).into(),
+			currency_id,
+			OrderSide::Buy,
+			price,
+			amount,
+		)?;
+		let order_id = crate::Pallet::<T>::next_order_id() - 1;
+	}: _(RawOrigin::Signed(who.clone()), currency_id, order_id)
+	verify {
+		assert_eq!(crate::Pallet::<T>::orders(currency_id, order_id), None);
+	}
+
+	match_orders {
+		let m in 1 .. T::MaxOrderMatches::get().min(T::MaxBookOrders::get());
+		let currency_id = T::stable_currency_id();
+		let native_currency_id = T::GetStp258NativeId::get();
+		let price = units::<T>(currency_id);
+		let amount = units::<T>(native_currency_id);
+		for i in 0..m {
+			let trader: T::AccountId = account("trader", i, SEED);
+			<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(native_currency_id, &trader, amount)?;
+			crate::Pallet::<T>::place_order(
+				RawOrigin::Signed(trader).into(),
+				currency_id,
+				OrderSide::Sell,
+				price,
+				amount,
+			)?;
+		}
+
+		let who: T::AccountId = whitelisted_caller();
+		let total = amount.saturating_mul(m.into());
+		<crate::Pallet<T> as Stp258Currency<T::AccountId>>::deposit(
+			currency_id,
+			&who,
+			crate::Pallet::<T>::order_cost(total, price)?,
+		)?;
+		crate::Pallet::<T>::place_order(
+			RawOrigin::Signed(who.clone()).into(),
+			currency_id,
+			OrderSide::Buy,
+			price,
+			total,
+		)?;
+	}: {
+		crate::Pallet::<T>::match_orders(currency_id);
+	}
+	verify {
+		assert!(crate::Pallet::<T>::sell_orders(currency_id).is_empty());
+		assert!(crate::Pallet::<T>::open_orders(&who).is_empty());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{ExtBuilder, Runtime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn transfer_non_native_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_transfer_non_native_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn transfer_native_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_transfer_native_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn transfer_all_non_native_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_transfer_all_non_native_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn transfer_all_native_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_transfer_all_native_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn transfer_keep_alive_non_native_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_transfer_keep_alive_non_native_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn transfer_keep_alive_native_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_transfer_keep_alive_native_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn transfer_batch() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_transfer_batch::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn on_initialize() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_on_initialize::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn lift_expired_locks() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_lift_expired_locks::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn merge_account() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_merge_account::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn update_balance_non_native_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_update_balance_non_native_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn update_balance_native_currency_creating() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_update_balance_native_currency_creating::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn update_balance_native_currency_killing() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_update_balance_native_currency_killing::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn register_stable_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_register_stable_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn update_stable_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_update_stable_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn deregister_stable_currency() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_deregister_stable_currency::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn set_supply_caps() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_set_supply_caps::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn pause() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_pause::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn unpause() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_unpause::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn pause_all() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_pause_all::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn unpause_all() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_unpause_all::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn expand_supply() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_expand_supply::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn contract_supply() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_contract_supply::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn claim() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_claim::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn bid() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_bid::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn settle_auction() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_settle_auction::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn buy_bonds() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_buy_bonds::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn set_seigniorage_shares() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_set_seigniorage_shares::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn add_liquidity() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_add_liquidity::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn remove_liquidity() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_remove_liquidity::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn swap_exact_in() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_swap_exact_in::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn swap_exact_out() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_swap_exact_out::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn place_order() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_place_order::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn cancel_order() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_cancel_order::<Runtime>());
+		});
+	}
+
+	#[test]
+	fn match_orders() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_match_orders::<Runtime>());
+		});
+	}
+}
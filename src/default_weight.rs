@@ -1,4 +1,7 @@
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.0
+//! Default weights for the SerpMarket module.
+//!
+//! These are estimates, not benchmark output. Regenerate this file with the
+//! Substrate benchmark CLI against `benchmarking.rs` before relying on them.
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -15,6 +18,33 @@ impl crate::WeightInfo for () {
 	fn transfer_native_currency() -> Weight {
 		(43_023_000 as Weight)
 	}
+	fn transfer_all_non_native_currency() -> Weight {
+		(176_204_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn transfer_all_native_currency() -> Weight {
+		(45_817_000 as Weight)
+	}
+	fn transfer_keep_alive_non_native_currency() -> Weight {
+		(178_630_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn transfer_keep_alive_native_currency() -> Weight {
+		(46_552_000 as Weight)
+	}
+	fn transfer_batch(c: u32) -> Weight {
+		(18_455_000 as Weight)
+			.saturating_add((174_918_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads((5 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	fn merge_account() -> Weight {
+		(312_706_000 as Weight)
+			.saturating_add(DbWeight::get().reads(11 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
 	fn update_balance_non_native_currency() -> Weight {
 		(137_440_000 as Weight)
 			.saturating_add(DbWeight::get().reads(5 as Weight))
@@ -26,4 +56,128 @@ impl crate::WeightInfo for () {
 	fn update_balance_native_currency_killing() -> Weight {
 		(62_595_000 as Weight)
 	}
+	fn register_stable_currency() -> Weight {
+		(24_512_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn update_stable_currency() -> Weight {
+		(23_874_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn deregister_stable_currency() -> Weight {
+		(22_106_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn on_initialize(c: u32) -> Weight {
+		(4_268_000 as Weight)
+			.saturating_add((168_917_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((6 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	fn set_supply_caps() -> Weight {
+		(21_309_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn pause() -> Weight {
+		(18_204_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn unpause() -> Weight {
+		(18_521_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn pause_all() -> Weight {
+		(16_937_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn unpause_all() -> Weight {
+		(17_082_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn expand_supply() -> Weight {
+		(187_402_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn contract_supply() -> Weight {
+		(162_855_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn lift_expired_locks(l: u32) -> Weight {
+		(3_102_000 as Weight)
+			.saturating_add((41_526_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(l as Weight)))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+			.saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(l as Weight)))
+	}
+	fn claim(s: u32) -> Weight {
+		(61_244_000 as Weight)
+			.saturating_add((1_837_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn bid() -> Weight {
+		(214_730_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(7 as Weight))
+	}
+	fn settle_auction(b: u32) -> Weight {
+		(38_415_000 as Weight)
+			.saturating_add((172_306_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().reads((5 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+			.saturating_add(DbWeight::get().writes((4 as Weight).saturating_mul(b as Weight)))
+	}
+	fn buy_bonds() -> Weight {
+		(143_518_000 as Weight)
+			.saturating_add(DbWeight::get().reads(7 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn set_seigniorage_shares() -> Weight {
+		(19_842_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn add_liquidity() -> Weight {
+		(236_417_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn remove_liquidity() -> Weight {
+		(228_905_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn swap_exact_in() -> Weight {
+		(204_332_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn swap_exact_out() -> Weight {
+		(206_178_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn place_order(o: u32) -> Weight {
+		(98_216_000 as Weight)
+			.saturating_add((412_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(DbWeight::get().reads(7 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn cancel_order(o: u32) -> Weight {
+		(84_903_000 as Weight)
+			.saturating_add((356_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn match_orders(m: u32) -> Weight {
+		(21_874_000 as Weight)
+			.saturating_add((186_541_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().reads((8 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+			.saturating_add(DbWeight::get().writes((7 as Weight).saturating_mul(m as Weight)))
+	}
 }
@@ -6,12 +6,12 @@ use frame_support::{
 	debug::native,
 	pallet_prelude::*,
 	traits::{
-		Currency as SetheumCurrency, ExistenceRequirement, Get, 
+		Currency as SetheumCurrency, ExistenceRequirement, Get, Imbalance,
 		LockableCurrency as SetheumLockableCurrency,
 		ReservableCurrency as SetheumReservableCurrency, WithdrawReasons,
 	},
 };
-use frame_system::{ensure_root, ensure_signed, pallet_prelude::*};
+use frame_system::{ensure_signed, pallet_prelude::*};
 use serp_traits::{
 	account::MergeAccount,
 	arithmetic::{Signed, SimpleArithmetic},
@@ -19,145 +19,2982 @@ use serp_traits::{
 	LockIdentifier, Stp258Currency, Stp258CurrencyExtended, Stp258CurrencyReservable, Stp258CurrencyLockable,
 };
 use orml_utilities::with_transaction_result;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_runtime::{
-	traits::{CheckedSub, MaybeSerializeDeserialize, StaticLookup, Zero},
-	DispatchError, DispatchResult,
+	helpers_128bit::multiply_by_rational,
+	traits::{
+		AccountIdConversion, AtLeast32BitUnsigned, CheckedSub, MaybeSerializeDeserialize, One, Saturating,
+		StaticLookup, UniqueSaturatedFrom, UniqueSaturatedInto, Zero,
+	},
+	DispatchError, DispatchResult, FixedI128, FixedPointNumber, ModuleId, Perbill,
 };
 use sp_std::{
 	convert::{TryFrom, TryInto},
 	fmt::Debug,
-	marker, result,
+	marker,
+	prelude::*,
+	result,
 };
 
-mod default_weight;
-mod mock;
-mod tests;
+pub mod benchmarking;
+mod default_weight;
+mod mock;
+mod tests;
+
+pub use module::*;
+
+/// Metadata of a stable currency the market may serp.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct StableCurrencyInfo<Balance> {
+	/// The peg target price, relative to the native currency.
+	pub peg: Balance,
+	/// The base unit of the currency.
+	pub base_unit: Balance,
+	/// Whether the currency may currently be serped.
+	pub enabled: bool,
+	/// The maximum amount a single `expand_supply` may expand the supply by.
+	pub max_expansion: Balance,
+}
+
+/// A cap on the supply adjustments of a stable currency.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct SupplyCap<Balance> {
+	/// The maximum absolute amount.
+	pub absolute: Balance,
+	/// The maximum amount, as a ratio of the total issuance.
+	pub ratio: Perbill,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> SupplyCap<Balance> {
+	/// The maximum amount under this cap for a currency of `total_issuance`.
+	pub fn limit(&self, total_issuance: Balance) -> Balance {
+		self.absolute.min(self.ratio.mul_floor(total_issuance))
+	}
+}
+
+/// The caps on the supply expansion and contraction of a stable currency.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct SupplyCaps<Balance> {
+	/// The cap on a single supply adjustment.
+	pub per_call: SupplyCap<Balance>,
+	/// The cap on the supply adjustments in a block.
+	pub per_block: SupplyCap<Balance>,
+	/// The cap on the supply adjustments in a `CapWindow`.
+	pub per_window: SupplyCap<Balance>,
+}
+
+/// The supply adjustments of a stable currency in a block.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct SupplyAdjustmentBucket<BlockNumber, Balance> {
+	/// The block of the adjustments.
+	pub block: BlockNumber,
+	/// The supply expanded by in `block`.
+	pub expanded: Balance,
+	/// The supply contracted by in `block`.
+	pub contracted: Balance,
+}
+
+/// The supply adjustments of a stable currency in the last `CapWindow`
+/// blocks.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct SupplyAdjustmentTally<BlockNumber, Balance> {
+	/// The adjustments of each block with any in the window, oldest first.
+	pub buckets: Vec<SupplyAdjustmentBucket<BlockNumber, Balance>>,
+}
+
+/// A supply adjustment of a stable currency.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum SupplyAdjustment<Balance> {
+	/// Expand the supply by the amount.
+	Expand(Balance),
+	/// Contract the supply by the amount.
+	Contract(Balance),
+}
+
+/// Computes how much to adjust the supply of a stable currency by.
+pub trait SupplyAdjuster<CurrencyId, Balance> {
+	/// The adjustment to make to the supply of `currency_id`, given its
+	/// `total_issuance`, its `peg` price and its current `market_price`, or
+	/// `None` if the supply should be left as is.
+	fn adjustment(
+		currency_id: CurrencyId,
+		total_issuance: Balance,
+		peg: Balance,
+		market_price: Balance,
+	) -> Option<SupplyAdjustment<Balance>>;
+
+	/// Called once the adjustment computed for `currency_id` at
+	/// `market_price` has been made, to commit any state the adjuster keeps.
+	fn on_adjusted(_currency_id: CurrencyId, _peg: Balance, _market_price: Balance) {}
+}
+
+/// State of the `PidAdjuster` of a stable currency.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct PidState {
+	/// The sum of all past relative price errors.
+	pub integral: FixedI128,
+	/// The relative price error of the last adjustment.
+	pub last_error: FixedI128,
+}
+
+/// A past supply adjustment of a stable currency.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub struct SupplyAdjustmentRecord<BlockNumber, Balance> {
+	/// The block the supply was adjusted in.
+	pub block_number: BlockNumber,
+	/// The direction and amount of the adjustment.
+	pub adjustment: SupplyAdjustment<Balance>,
+	/// The quote price the supply was adjusted at.
+	pub quote_price: Balance,
+	/// The total issuance after the adjustment.
+	pub total_issuance: Balance,
+}
+
+/// The balance of an account in a currency.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct AccountBalance<Balance> {
+	/// The free balance.
+	pub free: Balance,
+	/// The reserved balance.
+	pub reserved: Balance,
+}
+
+/// A vesting schedule. `total` vests linearly over `duration` blocks from
+/// `start`, but nothing vests before `start + cliff`. A `cliff` equal to the
+/// `duration` gives a pure cliff schedule.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct VestingSchedule<BlockNumber, Balance> {
+	/// The block vesting starts at.
+	pub start: BlockNumber,
+	/// The number of blocks after `start` before anything vests.
+	pub cliff: BlockNumber,
+	/// The number of blocks after `start` for `total` to fully vest.
+	pub duration: BlockNumber,
+	/// The amount vested by this schedule.
+	pub total: Balance,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy, Balance: AtLeast32BitUnsigned + Copy>
+	VestingSchedule<BlockNumber, Balance>
+{
+	/// Whether the schedule vests anything and its cliff is within its
+	/// duration.
+	pub fn is_valid(&self) -> bool {
+		!self.total.is_zero() && !self.duration.is_zero() && self.cliff <= self.duration
+	}
+
+	/// The amount still locked at block `now`.
+	pub fn locked_at(&self, now: BlockNumber) -> Balance {
+		let elapsed = now.saturating_sub(self.start);
+		if elapsed < self.cliff {
+			self.total
+		} else if elapsed >= self.duration {
+			Zero::zero()
+		} else {
+			let vested = Perbill::from_rational_approximation(elapsed, self.duration).mul_floor(self.total);
+			self.total.saturating_sub(vested)
+		}
+	}
+}
+
+/// A descending-price auction of native currency for a stable currency,
+/// burning the stable currency it raises.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct ContractionAuction<BlockNumber, Balance> {
+	/// The block the auction started at.
+	pub start: BlockNumber,
+	/// The last block bids are settled at.
+	pub end: BlockNumber,
+	/// The amount of stable currency to raise and burn.
+	pub target: Balance,
+	/// The amount of stable currency raised so far.
+	pub raised: Balance,
+	/// The price of a base unit of native currency at `start`.
+	pub start_price: Balance,
+	/// The price of a base unit of native currency at `end`.
+	pub floor_price: Balance,
+	/// The quote price of the contraction the auction was started for.
+	pub quote_price: Balance,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy, Balance: AtLeast32BitUnsigned + Copy>
+	ContractionAuction<BlockNumber, Balance>
+{
+	/// The price of a base unit of native currency at block `now`, falling
+	/// linearly from `start_price` to `floor_price`.
+	pub fn price_at(&self, now: BlockNumber) -> Balance {
+		let elapsed = now.saturating_sub(self.start).min(self.end.saturating_sub(self.start));
+		let duration = self.end.saturating_sub(self.start);
+		if duration.is_zero() {
+			return self.floor_price;
+		}
+		let drop = Perbill::from_rational_approximation(elapsed, duration)
+			.mul_floor(self.start_price.saturating_sub(self.floor_price));
+		self.start_price.saturating_sub(drop)
+	}
+}
+
+/// A SERP bond, redeemable for newly issued stable currency during later
+/// supply expansions until it expires.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct SerpBond<AccountId, BlockNumber, Balance> {
+	/// The account the bond is redeemed to.
+	pub owner: AccountId,
+	/// The amount of stable currency still to redeem.
+	pub face_value: Balance,
+	/// The block from which the bond can no longer be redeemed.
+	pub expires: BlockNumber,
+}
+
+/// The shares of newly issued stable currency paid to each seigniorage
+/// recipient. The shares must add up to 100%, with rounding dust going to
+/// the reserve buffer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct SeigniorageShares {
+	/// The share of the treasury.
+	pub treasury: Perbill,
+	/// The share of the serpers.
+	pub serpers: Perbill,
+	/// The share of the stakers of the native currency.
+	pub stakers: Perbill,
+	/// The share of the reserve buffer.
+	pub reserve_buffer: Perbill,
+}
+
+impl SeigniorageShares {
+	/// Whether the shares add up to exactly 100%.
+	pub fn is_valid(&self) -> bool {
+		let total = [self.treasury, self.serpers, self.stakers, self.reserve_buffer]
+			.iter()
+			.map(|share| share.deconstruct() as u64)
+			.sum::<u64>();
+		total == Perbill::from_percent(100).deconstruct() as u64
+	}
+}
+
+/// A recipient of seigniorage.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum SeigniorageRecipient {
+	/// The `TreasuryAccount`.
+	Treasury,
+	/// The `SerperAccount`, vesting under `ExpansionVesting`.
+	Serpers,
+	/// The `StakersAccount`.
+	Stakers,
+	/// The `ReserveBufferAccount`, taking the rounding remainder and the
+	/// shares too small for their recipient.
+	ReserveBuffer,
+}
+
+/// A constant-product liquidity pool between the native currency and a
+/// stable currency.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default)]
+pub struct LiquidityPool<Balance> {
+	/// The amount of native currency in the pool.
+	pub native_reserve: Balance,
+	/// The amount of stable currency in the pool.
+	pub stable_reserve: Balance,
+	/// The total liquidity shares issued by the pool.
+	pub total_shares: Balance,
+}
+
+/// The side of a limit order, buying or selling the native currency.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum OrderSide {
+	/// Buy the native currency with the stable currency.
+	Buy,
+	/// Sell the native currency for the stable currency.
+	Sell,
+}
+
+/// A resting limit order on the order book of a stable currency.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Order<AccountId, Balance> {
+	/// The account placing the order.
+	pub owner: AccountId,
+	/// Whether the order buys or sells the native currency.
+	pub side: OrderSide,
+	/// The limit price of a base unit of native currency, in the stable
+	/// currency.
+	pub price: Balance,
+	/// The amount of native currency still to fill.
+	pub amount: Balance,
+	/// The funds still reserved for the order, in the stable currency for
+	/// buy orders and in the native currency for sell orders.
+	pub reserved: Balance,
+}
+
+/// The named reserve auction bids are held in.
+pub const AUCTION_RESERVE_ID: ReserveIdentifier = *b"serpauct";
+
+/// The lock vesting schedules are enforced with.
+pub const VESTING_LOCK_ID: LockIdentifier = *b"serpvest";
+
+/// An identifier of a named reserve.
+pub type ReserveIdentifier = [u8; 8];
+
+/// An identifier of a SERP bond, unique per stable currency.
+pub type BondId = u64;
+
+/// The identifier of a limit order.
+pub type OrderId = u64;
+
+/// A multi-currency whose reserved balances can be set aside under named
+/// reserves, so that reservers cannot touch each other's funds.
+pub trait Stp258CurrencyNamedReservable<AccountId>: Stp258CurrencyReservable<AccountId> {
+	/// The amount of `currency_id` reserved by `who` under `id`.
+	fn reserved_balance_named(id: &ReserveIdentifier, currency_id: Self::CurrencyId, who: &AccountId)
+		-> Self::Balance;
+
+	/// Move `value` of `currency_id` from the free balance of `who` to its
+	/// reserved balance under `id`.
+	fn reserve_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> DispatchResult;
+
+	/// Move up to `value` of `currency_id` reserved under `id` back to the
+	/// free balance of `who`, returning the amount that could not be
+	/// unreserved.
+	fn unreserve_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> Self::Balance;
+
+	/// Slash up to `value` of `currency_id` reserved by `who` under `id`,
+	/// returning the amount that could not be slashed.
+	fn slash_reserved_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> Self::Balance;
+
+	/// Move up to `value` of `currency_id` reserved by `slashed` under `id`
+	/// to the `status` balance of `beneficiary`, under the same `id` when
+	/// reserved, returning the amount that could not be moved.
+	fn repatriate_reserved_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		slashed: &AccountId,
+		beneficiary: &AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError>;
+}
+
+/// A single currency whose reserved balance can be set aside under named
+/// reserves, so that reservers cannot touch each other's funds.
+pub trait Stp258AssetNamedReservable<AccountId>: Stp258AssetReservable<AccountId> {
+	/// The amount reserved by `who` under `id`.
+	fn reserved_balance_named(id: &ReserveIdentifier, who: &AccountId) -> Self::Balance;
+
+	/// Move `value` from the free balance of `who` to its reserved balance
+	/// under `id`.
+	fn reserve_named(id: &ReserveIdentifier, who: &AccountId, value: Self::Balance) -> DispatchResult;
+
+	/// Move up to `value` reserved under `id` back to the free balance of
+	/// `who`, returning the amount that could not be unreserved.
+	fn unreserve_named(id: &ReserveIdentifier, who: &AccountId, value: Self::Balance) -> Self::Balance;
+
+	/// Slash up to `value` reserved by `who` under `id`, returning the amount
+	/// that could not be slashed.
+	fn slash_reserved_named(id: &ReserveIdentifier, who: &AccountId, value: Self::Balance) -> Self::Balance;
+
+	/// Move up to `value` reserved by `slashed` under `id` to the `status`
+	/// balance of `beneficiary`, under the same `id` when reserved, returning
+	/// the amount that could not be moved.
+	fn repatriate_reserved_named(
+		id: &ReserveIdentifier,
+		slashed: &AccountId,
+		beneficiary: &AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError>;
+}
+
+/// A multi-currency whose locks and withdrawals can be limited to a set of
+/// `WithdrawReasons`, rather than always applying to all of them.
+///
+/// Only the native currency supports reasons other than
+/// `WithdrawReasons::all()`; every method fails with any other reasons for
+/// the other currencies.
+pub trait Stp258CurrencyLockableWithReasons<AccountId>: Stp258CurrencyLockable<AccountId> {
+	/// Create or amend the lock `lock_id` of `who`, restricting `reasons`.
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Extend the lock `lock_id` of `who`, restricting `reasons`.
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Ensure `amount` of `currency_id` can be withdrawn from `who` for
+	/// `reasons`.
+	fn ensure_can_withdraw_with_reasons(
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Withdraw `amount` of `currency_id` from `who` for `reasons`.
+	fn withdraw_with_reasons(
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+}
+
+/// A single currency whose locks and withdrawals can be limited to a set of
+/// `WithdrawReasons`, rather than always applying to all of them.
+///
+/// A non-native `Currency` fails every method with reasons other than
+/// `WithdrawReasons::all()`.
+pub trait Stp258AssetLockableWithReasons<AccountId>: Stp258AssetLockable<AccountId> {
+	/// Create or amend the lock `lock_id` of `who`, restricting `reasons`.
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Extend the lock `lock_id` of `who`, restricting `reasons`.
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Ensure `amount` can be withdrawn from `who` for `reasons`.
+	fn ensure_can_withdraw_with_reasons(who: &AccountId, amount: Self::Balance, reasons: WithdrawReasons)
+		-> DispatchResult;
+
+	/// Withdraw `amount` from `who` for `reasons`.
+	fn withdraw_with_reasons(who: &AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult;
+}
+
+/// A multi-currency whose locks can be lifted automatically at a given
+/// moment.
+pub trait Stp258CurrencyTimedLockable<AccountId>: Stp258CurrencyLockable<AccountId> {
+	/// Create or amend the lock `lock_id` of `who`, lifting it at `until`.
+	fn set_lock_until(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		until: Self::Moment,
+	) -> DispatchResult;
+
+	/// The moment the lock `lock_id` of `who` is lifted, or `None` if it is
+	/// not timed.
+	fn lock_expiry(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &AccountId) -> Option<Self::Moment>;
+}
+
+/// A single currency whose locks can be lifted automatically at a given
+/// moment.
+pub trait Stp258AssetTimedLockable<AccountId>: Stp258AssetLockable<AccountId> {
+	/// Create or amend the lock `lock_id` of `who`, lifting it at `until`.
+	fn set_lock_until(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		until: Self::Moment,
+	) -> DispatchResult;
+
+	/// The moment the lock `lock_id` of `who` is lifted, or `None` if it is
+	/// not timed.
+	fn lock_expiry(lock_id: LockIdentifier, who: &AccountId) -> Option<Self::Moment>;
+}
+
+/// A single currency whose transfers can refuse to reap the sender.
+pub trait Stp258AssetKeepAlive<AccountId>: Stp258Asset<AccountId> {
+	/// Transfer `amount` from `from` to `to`, failing if it would reap
+	/// `from`.
+	fn transfer_keep_alive(from: &AccountId, to: &AccountId, amount: Self::Balance) -> DispatchResult;
+}
+
+/// A source of market prices for the stable currencies.
+pub trait PriceProvider<CurrencyId, Price> {
+	/// The current market price of `currency_id`, in the same unit as its
+	/// `peg`, or `None` if no price is available.
+	fn get_price(currency_id: CurrencyId) -> Option<Price>;
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	pub trait WeightInfo {
+		fn transfer_non_native_currency() -> Weight;
+		fn transfer_native_currency() -> Weight;
+		fn transfer_all_non_native_currency() -> Weight;
+		fn transfer_all_native_currency() -> Weight;
+		fn transfer_keep_alive_non_native_currency() -> Weight;
+		fn transfer_keep_alive_native_currency() -> Weight;
+		fn transfer_batch(c: u32) -> Weight;
+		fn merge_account() -> Weight;
+		fn update_balance_non_native_currency() -> Weight;
+		fn update_balance_native_currency_creating() -> Weight;
+		fn update_balance_native_currency_killing() -> Weight;
+		fn register_stable_currency() -> Weight;
+		fn update_stable_currency() -> Weight;
+		fn deregister_stable_currency() -> Weight;
+		fn on_initialize(c: u32) -> Weight;
+		fn set_supply_caps() -> Weight;
+		fn pause() -> Weight;
+		fn unpause() -> Weight;
+		fn pause_all() -> Weight;
+		fn unpause_all() -> Weight;
+		fn expand_supply() -> Weight;
+		fn contract_supply() -> Weight;
+		fn lift_expired_locks(l: u32) -> Weight;
+		fn claim(s: u32) -> Weight;
+		fn bid() -> Weight;
+		fn settle_auction(b: u32) -> Weight;
+		fn buy_bonds() -> Weight;
+		fn set_seigniorage_shares() -> Weight;
+		fn add_liquidity() -> Weight;
+		fn remove_liquidity() -> Weight;
+		fn swap_exact_in() -> Weight;
+		fn swap_exact_out() -> Weight;
+		fn place_order(o: u32) -> Weight;
+		fn cancel_order(o: u32) -> Weight;
+		fn match_orders(m: u32) -> Weight;
+	}
+
+	pub(crate) type BalanceOf<T> =
+		<<T as Config>::Stp258Currency as Stp258Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub(crate) type CurrencyIdOf<T> =
+		<<T as Config>::Stp258Currency as Stp258Currency<<T as frame_system::Config>::AccountId>>::CurrencyId;
+	pub(crate) type AmountOf<T> =
+		<<T as Config>::Stp258Currency as Stp258CurrencyExtended<<T as frame_system::Config>::AccountId>>::Amount;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		type Stp258Currency: MergeAccount<Self::AccountId>
+			+ Stp258CurrencyExtended<Self::AccountId>
+			+ Stp258CurrencyLockable<Self::AccountId>
+			+ Stp258CurrencyReservable<Self::AccountId>
+			+ SerpMarket<Self::AccountId>;
+
+		type Stp258Native: Stp258AssetExtended<Self::AccountId, Balance = BalanceOf<Self>, Amount = AmountOf<Self>>
+			+ Stp258AssetLockableWithReasons<Self::AccountId, Balance = BalanceOf<Self>>
+			+ Stp258AssetKeepAlive<Self::AccountId, Balance = BalanceOf<Self>>
+			+ Stp258AssetReservable<Self::AccountId, Balance = BalanceOf<Self>>;
+
+		#[pallet::constant]
+		type GetStp258NativeId: Get<CurrencyIdOf<Self>>;
+
+		/// The market price source of the stable currencies.
+		type PriceProvider: PriceProvider<CurrencyIdOf<Self>, BalanceOf<Self>>;
+
+		/// The number of blocks between automatic supply adjustments.
+		#[pallet::constant]
+		type AdjustmentFrequency: Get<Self::BlockNumber>;
+
+		/// The supply adjustment curve of the automatic supply adjustments.
+		type SupplyAdjuster: SupplyAdjuster<CurrencyIdOf<Self>, BalanceOf<Self>>;
+
+		/// The number of blocks of the rolling window the per window supply
+		/// caps apply to.
+		#[pallet::constant]
+		type CapWindow: Get<Self::BlockNumber>;
+
+		/// The origin which may pause and unpause the market.
+		type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may update balances and manage the stable
+		/// currencies.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may serp up and serp down the supply of the
+		/// stable currencies by hand.
+		type SerpOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The number of past supply adjustments kept for each stable
+		/// currency.
+		#[pallet::constant]
+		type MaxSupplyHistory: Get<u32>;
+
+		/// The maximum number of transfers in a `transfer_batch`.
+		#[pallet::constant]
+		type MaxTransferBatchSize: Get<u32>;
+
+		/// The maximum number of timed locks lifted in a single block.
+		#[pallet::constant]
+		type MaxLockExpiries: Get<u32>;
+
+		/// The account the serpers' expansion proceeds are paid to.
+		type SerperAccount: Get<Self::AccountId>;
+
+		/// The `(cliff, duration)` of the vesting schedule the expansion
+		/// proceeds of `SerperAccount` are paid into, or `None` to pay them
+		/// out liquid.
+		type ExpansionVesting: Get<Option<(Self::BlockNumber, Self::BlockNumber)>>;
+
+		/// The maximum number of vesting schedules of an account in a currency.
+		#[pallet::constant]
+		type MaxVestingSchedules: Get<u32>;
+
+		/// The number of blocks a contraction auction runs for, or zero to
+		/// contract supply directly through `Stp258Currency`.
+		#[pallet::constant]
+		type AuctionDuration: Get<Self::BlockNumber>;
+
+		/// How far above the quote price contraction auctions start.
+		#[pallet::constant]
+		type AuctionStartPremium: Get<Perbill>;
+
+		/// How far below the quote price contraction auctions end.
+		#[pallet::constant]
+		type AuctionFloorDiscount: Get<Perbill>;
+
+		/// The maximum number of bids on an auction in a single block.
+		#[pallet::constant]
+		type MaxAuctionBids: Get<u32>;
+
+		/// The number of blocks a SERP bond can be redeemed for, or zero to
+		/// let contractions the reserve of `SerperAccount` can't cover fail
+		/// instead of offering bonds.
+		#[pallet::constant]
+		type BondDuration: Get<Self::BlockNumber>;
+
+		/// The face value of a SERP bond over the stable currency burnt for
+		/// it.
+		#[pallet::constant]
+		type BondPremium: Get<Perbill>;
+
+		/// The maximum number of bonds redeemed or expired in a single supply
+		/// expansion.
+		#[pallet::constant]
+		type MaxBondRedemptions: Get<u32>;
+
+		/// The account the treasury share of seigniorage is paid to.
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// The account the native currency stakers' share of seigniorage is
+		/// paid to.
+		type StakersAccount: Get<Self::AccountId>;
+
+		/// The account the reserve buffer share of seigniorage is paid to.
+		type ReserveBufferAccount: Get<Self::AccountId>;
+
+		/// The module id of the account holding the liquidity pools.
+		#[pallet::constant]
+		type PoolModuleId: Get<ModuleId>;
+
+		/// The fee on swaps, kept by the liquidity pools.
+		#[pallet::constant]
+		type SwapFee: Get<Perbill>;
+
+		/// The maximum number of open orders of an account.
+		#[pallet::constant]
+		type MaxOpenOrders: Get<u32>;
+
+		/// The maximum number of order matches per stable currency in a
+		/// single block.
+		#[pallet::constant]
+		type MaxOrderMatches: Get<u32>;
+
+		/// The maximum number of orders on each side of the order book of a
+		/// stable currency.
+		#[pallet::constant]
+		type MaxBookOrders: Get<u32>;
+
+		/// Weight information for extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Unable to convert the Amount type into Balance.
+		AmountIntoBalanceFailed,
+		/// Balance is too low.
+		BalanceTooLow,
+		/// The currency given as the native serping currency is not the native currency.
+		UnrecognisedNativeCurrency,
+		/// The native currency cannot be serped as a stable currency.
+		NativeCurrencyCannotBeSerped,
+		/// The stable currency is not registered.
+		StableCurrencyNotRegistered,
+		/// The stable currency is already registered.
+		StableCurrencyAlreadyRegistered,
+		/// The stable currency is registered but disabled.
+		StableCurrencyDisabled,
+		/// The supply expansion exceeds the maximum expansion of the stable currency.
+		ExceedsMaxExpansion,
+		/// No market price is available for the stable currency.
+		PriceUnavailable,
+		/// The supply adjustment exceeds the per call supply cap.
+		ExceedsCallCap,
+		/// The supply adjustments exceed the per block supply cap.
+		ExceedsBlockCap,
+		/// The supply adjustments exceed the per window supply cap.
+		ExceedsWindowCap,
+		/// The currency is paused.
+		CurrencyPaused,
+		/// The whole market is paused.
+		MarketPaused,
+		/// The transfer would reap the transactor.
+		KeepAlive,
+		/// The batch has more than `MaxTransferBatchSize` transfers.
+		TooManyTransfers,
+		/// The account has balances locked.
+		AccountHasLocks,
+		/// The account has balances reserved.
+		AccountHasReserves,
+		/// Non-native locks and withdrawals always apply to all withdraw
+		/// reasons.
+		WithdrawReasonsNotSupported,
+		/// The lock would expire at or before the current block.
+		LockExpiryInPast,
+		/// The block already lifts `MaxLockExpiries` timed locks.
+		TooManyLockExpiries,
+		/// The deposit is below the existential deposit of a new account.
+		BelowExistentialDeposit,
+		/// The vesting schedule vests nothing, or has a cliff after its end.
+		InvalidVestingSchedule,
+		/// The account already has `MaxVestingSchedules` vesting schedules.
+		TooManyVestingSchedules,
+		/// The stable currency is already being auctioned.
+		AuctionInProgress,
+		/// The stable currency is not being auctioned.
+		AuctionNotFound,
+		/// The auction already has `MaxAuctionBids` bids this block.
+		TooManyBids,
+		/// No bonds are offered for the stable currency.
+		NoBondOffer,
+		/// The seigniorage shares do not add up to 100%.
+		InvalidSeigniorageShares,
+		/// Swaps are only between the native currency and a stable currency.
+		InvalidSwapPair,
+		/// The liquidity amounts are zero or too small to issue any share.
+		InvalidLiquidityAmount,
+		/// The pool has too little liquidity.
+		InsufficientLiquidity,
+		/// The account has too few liquidity shares.
+		InsufficientShares,
+		/// The amounts are worse than the given limits.
+		SlippageExceeded,
+		/// The order amount or price is zero, or the order is too small to
+		/// cost anything.
+		InvalidOrder,
+		/// The account already has `MaxOpenOrders` open orders.
+		TooManyOpenOrders,
+		/// The order does not exist or is not owned by the account.
+		OrderNotFound,
+		/// The reserved funds of an order no longer cover its fill.
+		OrderFundsMissing,
+		/// The side of the order book already holds `MaxBookOrders` orders.
+		OrderBookFull,
+		/// The account has open orders.
+		AccountHasOpenOrders,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Currency transfer success. [currency_id, from, to, amount]
+		Transferred(CurrencyIdOf<T>, T::AccountId, T::AccountId, BalanceOf<T>),
+		/// Update balance success. [currency_id, who, amount]
+		BalanceUpdated(CurrencyIdOf<T>, T::AccountId, AmountOf<T>),
+		/// Deposit success. [currency_id, who, amount]
+		Deposited(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Withdraw success. [currency_id, who, amount]
+		Withdrawn(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		// Supply Expansion Successful. \[currency_id, expand_by\]
+		SerpedUpSupply(CurrencyIdOf<T>, BalanceOf<T>),
+		/// Supply Contraction Successful. \[currency_id, contract_by\]
+		SerpedDownSupply(CurrencyIdOf<T>, BalanceOf<T>),
+		/// Stable currency registered. \[currency_id, info\]
+		StableCurrencyRegistered(CurrencyIdOf<T>, StableCurrencyInfo<BalanceOf<T>>),
+		/// Stable currency updated. \[currency_id, info\]
+		StableCurrencyUpdated(CurrencyIdOf<T>, StableCurrencyInfo<BalanceOf<T>>),
+		/// Stable currency deregistered. \[currency_id\]
+		StableCurrencyDeregistered(CurrencyIdOf<T>),
+		/// Supply caps updated. \[currency_id, caps\]
+		SupplyCapsUpdated(CurrencyIdOf<T>, Option<SupplyCaps<BalanceOf<T>>>),
+		/// Supply adjustment rejected for breaching a supply cap. \[currency_id,
+		/// adjustment\]
+		SupplyCapBreached(CurrencyIdOf<T>, SupplyAdjustment<BalanceOf<T>>),
+		/// Currency paused. \[currency_id\]
+		CurrencyPaused(CurrencyIdOf<T>),
+		/// Currency unpaused. \[currency_id\]
+		CurrencyUnpaused(CurrencyIdOf<T>),
+		/// The whole market paused.
+		MarketPaused,
+		/// The whole market unpaused.
+		MarketUnpaused,
+		/// Reserve success. \[currency_id, who, amount\]
+		Reserved(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Unreserve success. \[currency_id, who, unreserved\]
+		Unreserved(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Free balance slashed. \[currency_id, who, slashed\]
+		Slashed(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Reserved balance slashed. \[currency_id, who, slashed\]
+		ReserveSlashed(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Reserved balance repatriated. \[currency_id, from, to, repatriated,
+		/// status\]
+		ReserveRepatriated(CurrencyIdOf<T>, T::AccountId, T::AccountId, BalanceOf<T>, BalanceStatus),
+		/// Lock set. \[lock_id, currency_id, who, amount\]
+		LockSet(LockIdentifier, CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Lock extended, to the resulting lock amount. \[lock_id, currency_id,
+		/// who, amount\]
+		LockExtended(LockIdentifier, CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Lock removed. \[lock_id, currency_id, who\]
+		LockRemoved(LockIdentifier, CurrencyIdOf<T>, T::AccountId),
+		/// Account merged into another. \[source, dest, moved\]
+		AccountMerged(T::AccountId, T::AccountId, Vec<(CurrencyIdOf<T>, BalanceOf<T>)>),
+		/// Vesting schedule added. \[currency_id, who, schedule\]
+		VestingScheduleAdded(CurrencyIdOf<T>, T::AccountId, VestingSchedule<T::BlockNumber, BalanceOf<T>>),
+		/// Vested balance claimed. \[currency_id, who, still_locked\]
+		VestingClaimed(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Contraction auction started. \[currency_id, auction\]
+		AuctionStarted(CurrencyIdOf<T>, ContractionAuction<T::BlockNumber, BalanceOf<T>>),
+		/// Bid placed. \[currency_id, who, amount\]
+		BidPlaced(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Bid settled. \[currency_id, who, paid, native_received\]
+		BidSettled(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// Contraction auction closed. \[currency_id, raised\]
+		AuctionClosed(CurrencyIdOf<T>, BalanceOf<T>),
+		/// Bonds offered for a contraction. \[currency_id, amount\]
+		BondsOffered(CurrencyIdOf<T>, BalanceOf<T>),
+		/// Bond issued. \[currency_id, bond_id, owner, paid, face_value\]
+		BondIssued(CurrencyIdOf<T>, BondId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// Bond redeemed. \[currency_id, bond_id, owner, amount\]
+		BondRedeemed(CurrencyIdOf<T>, BondId, T::AccountId, BalanceOf<T>),
+		/// Bond expired unredeemed. \[currency_id, bond_id, owner, face_value\]
+		BondExpired(CurrencyIdOf<T>, BondId, T::AccountId, BalanceOf<T>),
+		/// Seigniorage shares updated. \[shares\]
+		SeigniorageSharesUpdated(Option<SeigniorageShares>),
+		/// Seigniorage paid. \[currency_id, recipient, who, amount\]
+		SeignioragePaid(CurrencyIdOf<T>, SeigniorageRecipient, T::AccountId, BalanceOf<T>),
+		/// Liquidity added. \[who, stable_currency_id, native_amount, stable_amount, shares\]
+		LiquidityAdded(T::AccountId, CurrencyIdOf<T>, BalanceOf<T>, BalanceOf<T>, BalanceOf<T>),
+		/// Liquidity removed. \[who, stable_currency_id, native_amount, stable_amount, shares\]
+		LiquidityRemoved(T::AccountId, CurrencyIdOf<T>, BalanceOf<T>, BalanceOf<T>, BalanceOf<T>),
+		/// Swapped. \[who, currency_in, currency_out, amount_in, amount_out\]
+		Swapped(T::AccountId, CurrencyIdOf<T>, CurrencyIdOf<T>, BalanceOf<T>, BalanceOf<T>),
+		/// Order placed. \[currency_id, order_id, who, side, price, amount\]
+		OrderPlaced(CurrencyIdOf<T>, OrderId, T::AccountId, OrderSide, BalanceOf<T>, BalanceOf<T>),
+		/// Orders matched. \[currency_id, buy_order_id, sell_order_id, amount, price\]
+		OrdersMatched(CurrencyIdOf<T>, OrderId, OrderId, BalanceOf<T>, BalanceOf<T>),
+		/// Order cancelled. \[currency_id, order_id, who\]
+		OrderCancelled(CurrencyIdOf<T>, OrderId, T::AccountId),
+	}
+
+	/// The stable currencies the market may serp, with their metadata.
+	///
+	/// StableCurrencies: map CurrencyId => Option<StableCurrencyInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn stable_currencies)]
+	pub type StableCurrencies<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, StableCurrencyInfo<BalanceOf<T>>, OptionQuery>;
+
+	/// The state of the `PidAdjuster` for each stable currency.
+	///
+	/// PidStates: map CurrencyId => PidState
+	#[pallet::storage]
+	#[pallet::getter(fn pid_states)]
+	pub type PidStates<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, PidState, ValueQuery>;
+
+	/// The caps on the supply adjustments of each stable currency. Currencies
+	/// without caps are only bound by their `max_expansion`.
+	///
+	/// SupplyCapsOf: map CurrencyId => Option<SupplyCaps>
+	#[pallet::storage]
+	#[pallet::getter(fn supply_caps)]
+	pub type SupplyCapsOf<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, SupplyCaps<BalanceOf<T>>, OptionQuery>;
+
+	/// The supply adjustments of each stable currency counted towards the
+	/// per block and per window supply caps, over a window rolling over the
+	/// last `CapWindow` blocks.
+	///
+	/// SupplyAdjustmentTallies: map CurrencyId => SupplyAdjustmentTally
+	#[pallet::storage]
+	#[pallet::getter(fn supply_adjustment_tallies)]
+	pub type SupplyAdjustmentTallies<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		CurrencyIdOf<T>,
+		SupplyAdjustmentTally<T::BlockNumber, BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	/// The currencies which are paused, so they can be neither serped nor
+	/// transferred nor have their balances updated.
+	///
+	/// PausedCurrencies: map CurrencyId => bool
+	#[pallet::storage]
+	#[pallet::getter(fn paused_currencies)]
+	pub type PausedCurrencies<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, bool, ValueQuery>;
+
+	/// Whether the whole market is paused, as if every currency were paused.
+	///
+	/// MarketPaused: bool
+	#[pallet::storage]
+	#[pallet::getter(fn market_paused)]
+	pub type MarketPaused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The past supply adjustments of each stable currency, as a ring buffer
+	/// of `MaxSupplyHistory` slots.
+	///
+	/// SupplyHistory: double_map CurrencyId, SlotIndex => Option<SupplyAdjustmentRecord>
+	#[pallet::storage]
+	pub type SupplyHistory<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CurrencyIdOf<T>,
+		Twox64Concat,
+		u32,
+		SupplyAdjustmentRecord<T::BlockNumber, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// The number of supply adjustments ever recorded for each stable
+	/// currency. The next record goes to slot `count % MaxSupplyHistory`.
+	///
+	/// SupplyHistoryCount: map CurrencyId => u32
+	#[pallet::storage]
+	#[pallet::getter(fn supply_history_count)]
+	pub type SupplyHistoryCount<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, u32, ValueQuery>;
+
+	/// The named reserves of each account.
+	///
+	/// NamedReserves: double_map AccountId, (CurrencyId, ReserveIdentifier) => Option<Balance>
+	#[pallet::storage]
+	pub type NamedReserves<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		(CurrencyIdOf<T>, ReserveIdentifier),
+		BalanceOf<T>,
+		OptionQuery,
+	>;
+
+	/// The amount of each lock set through the pallet.
+	///
+	/// LockAmounts: double_map AccountId, (CurrencyId, LockIdentifier) => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn lock_amounts)]
+	pub type LockAmounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		(CurrencyIdOf<T>, LockIdentifier),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// The block at which each timed lock is lifted.
+	///
+	/// LockExpiries: double_map AccountId, (CurrencyId, LockIdentifier) => Option<BlockNumber>
+	#[pallet::storage]
+	pub type LockExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		(CurrencyIdOf<T>, LockIdentifier),
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// The timed locks to lift at each block. Entries whose lock was since
+	/// removed or re-set are skipped.
+	///
+	/// ExpiringLocks: map BlockNumber => Vec<(LockIdentifier, CurrencyId, AccountId)>
+	#[pallet::storage]
+	pub type ExpiringLocks<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Vec<(LockIdentifier, CurrencyIdOf<T>, T::AccountId)>,
+		ValueQuery,
+	>;
+
+	/// The vesting schedules of each account and currency.
+	///
+	/// VestingSchedules: double_map AccountId, CurrencyId => Vec<VestingSchedule>
+	#[pallet::storage]
+	#[pallet::getter(fn vesting_schedules)]
+	pub type VestingSchedules<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		CurrencyIdOf<T>,
+		Vec<VestingSchedule<T::BlockNumber, BalanceOf<T>>>,
+		ValueQuery,
+	>;
+
+	/// The running contraction auction of each stable currency.
+	///
+	/// Auctions: map CurrencyId => Option<ContractionAuction>
+	#[pallet::storage]
+	#[pallet::getter(fn auctions)]
+	pub type Auctions<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, ContractionAuction<T::BlockNumber, BalanceOf<T>>, OptionQuery>;
+
+	/// The number of running contraction auctions.
+	///
+	/// AuctionCount: u32
+	#[pallet::storage]
+	#[pallet::getter(fn auction_count)]
+	pub type AuctionCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The bids on each auction awaiting settlement at the end of the block.
+	///
+	/// AuctionBids: map CurrencyId => Vec<(AccountId, Balance)>
+	#[pallet::storage]
+	#[pallet::getter(fn auction_bids)]
+	pub type AuctionBids<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, Vec<(T::AccountId, BalanceOf<T>)>, ValueQuery>;
+
+	/// The amount of each stable currency still to contract by selling bonds.
+	///
+	/// BondOffers: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn bond_offers)]
+	pub type BondOffers<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// The quote price of the contraction the bonds of each stable currency
+	/// are offered for.
+	///
+	/// BondOfferQuotes: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn bond_offer_quotes)]
+	pub type BondOfferQuotes<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// The outstanding bonds of each stable currency.
+	///
+	/// Bonds: double_map CurrencyId, BondId => Option<SerpBond>
+	#[pallet::storage]
+	#[pallet::getter(fn bonds)]
+	pub type Bonds<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CurrencyIdOf<T>,
+		Twox64Concat,
+		BondId,
+		SerpBond<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// The redemption queue of the bonds of each stable currency, as the id
+	/// of the oldest outstanding bond and the id of the next bond.
+	///
+	/// BondQueue: map CurrencyId => (BondId, BondId)
+	#[pallet::storage]
+	#[pallet::getter(fn bond_queue)]
+	pub type BondQueue<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, (BondId, BondId), ValueQuery>;
+
+	/// How newly issued stable currency is shared out, or `None` to leave it
+	/// to `Stp258Currency`.
+	///
+	/// SeigniorageDistribution: Option<SeigniorageShares>
+	#[pallet::storage]
+	#[pallet::getter(fn seigniorage_shares)]
+	pub type SeigniorageDistribution<T: Config> = StorageValue<_, SeigniorageShares, OptionQuery>;
+
+	/// The liquidity pools, keyed by `(native_currency_id, stable_currency_id)`.
+	///
+	/// Pools: map (CurrencyId, CurrencyId) => LiquidityPool
+	#[pallet::storage]
+	#[pallet::getter(fn pools)]
+	pub type Pools<T: Config> =
+		StorageMap<_, Twox64Concat, (CurrencyIdOf<T>, CurrencyIdOf<T>), LiquidityPool<BalanceOf<T>>, ValueQuery>;
+
+	/// The liquidity shares of each account in each pool.
+	///
+	/// LiquidityShares: double_map (CurrencyId, CurrencyId), AccountId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn liquidity_shares)]
+	pub type LiquidityShares<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		(CurrencyIdOf<T>, CurrencyIdOf<T>),
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// The open limit orders of each stable currency.
+	///
+	/// Orders: double_map CurrencyId, OrderId => Option<Order>
+	#[pallet::storage]
+	#[pallet::getter(fn orders)]
+	pub type Orders<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CurrencyIdOf<T>,
+		Twox64Concat,
+		OrderId,
+		Order<T::AccountId, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// The id of the next limit order.
+	///
+	/// NextOrderId: OrderId
+	#[pallet::storage]
+	#[pallet::getter(fn next_order_id)]
+	pub type NextOrderId<T: Config> = StorageValue<_, OrderId, ValueQuery>;
+
+	/// The `(price, order_id)` of the buy orders of each stable currency,
+	/// highest price first, then oldest first.
+	///
+	/// BuyOrders: map CurrencyId => Vec<(Balance, OrderId)>
+	#[pallet::storage]
+	#[pallet::getter(fn buy_orders)]
+	pub type BuyOrders<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, Vec<(BalanceOf<T>, OrderId)>, ValueQuery>;
+
+	/// The number of stable currencies with buy orders.
+	///
+	/// BuyOrderBookCount: u32
+	#[pallet::storage]
+	#[pallet::getter(fn buy_order_book_count)]
+	pub type BuyOrderBookCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The `(price, order_id)` of the sell orders of each stable currency,
+	/// lowest price first, then oldest first.
+	///
+	/// SellOrders: map CurrencyId => Vec<(Balance, OrderId)>
+	#[pallet::storage]
+	#[pallet::getter(fn sell_orders)]
+	pub type SellOrders<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, Vec<(BalanceOf<T>, OrderId)>, ValueQuery>;
+
+	/// The open orders of each account.
+	///
+	/// OpenOrders: map AccountId => Vec<(CurrencyId, OrderId)>
+	#[pallet::storage]
+	#[pallet::getter(fn open_orders)]
+	pub type OpenOrders<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<(CurrencyIdOf<T>, OrderId)>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub stable_currencies: Vec<(CurrencyIdOf<T>, StableCurrencyInfo<BalanceOf<T>>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			GenesisConfig {
+				stable_currencies: vec![],
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			self.stable_currencies.iter().for_each(|(currency_id, info)| {
+				assert!(
+					*currency_id != T::GetStp258NativeId::get(),
+					"The native currency cannot be a stable currency"
+				);
+				StableCurrencies::<T>::insert(currency_id, info);
+			});
+		}
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Lift the timed locks expiring at `now`, and adjust the supply of
+		/// every enabled stable currency towards its peg every
+		/// `AdjustmentFrequency` blocks. Also accounts for the weight of
+		/// `on_finalize`.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let lifted = Self::lift_expired_locks(now);
+			T::WeightInfo::lift_expired_locks(lifted)
+				.saturating_add(Self::serp_stable_currencies(now))
+				.saturating_add(Self::on_finalize_weight())
+		}
+
+		/// Settle the bids on the contraction auctions placed in this block,
+		/// and match the crossing limit orders of each stable currency.
+		fn on_finalize(now: T::BlockNumber) {
+			for (currency_id, auction) in Auctions::<T>::iter().collect::<Vec<_>>() {
+				Self::settle_auction(currency_id, auction, now);
+			}
+			for (currency_id, _) in BuyOrders::<T>::iter().collect::<Vec<_>>() {
+				if Self::ensure_not_paused(currency_id).is_ok() {
+					Self::match_orders(currency_id);
+				}
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Transfer some balance to another account under `currency_id`.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(T::WeightInfo::transfer_non_native_currency())]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &from, &to, amount)?;
+			Ok(().into())
+		}
+
+		/// Transfer some native currency to another account.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(T::WeightInfo::transfer_native_currency())]
+		pub fn transfer_native_currency(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			Self::ensure_not_paused(T::GetStp258NativeId::get())?;
+			T::Stp258Native::transfer(&from, &to, amount)?;
+
+			Self::deposit_event(Event::Transferred(T::GetStp258NativeId::get(), from, to, amount));
+			Ok(().into())
+		}
+
+		/// Transfer all the transferable balance under `currency_id` to
+		/// another account, leaving the balance frozen by locks.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(if *currency_id == T::GetStp258NativeId::get() {
+			T::WeightInfo::transfer_all_native_currency()
+		} else {
+			T::WeightInfo::transfer_all_non_native_currency()
+		})]
+		pub fn transfer_all(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			let amount = Self::transferable_balance(currency_id, &from);
+			<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &from, &to, amount)?;
+			Ok(().into())
+		}
+
+		/// Transfer some balance to another account under `currency_id`,
+		/// refusing to reap the transactor.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(if *currency_id == T::GetStp258NativeId::get() {
+			T::WeightInfo::transfer_keep_alive_native_currency()
+		} else {
+			T::WeightInfo::transfer_keep_alive_non_native_currency()
+		})]
+		pub fn transfer_keep_alive(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			Self::transfer_keep_alive_under(currency_id, &from, &to, amount)?;
+			Ok(().into())
+		}
+
+		/// Transfer balances under several currencies to several accounts at
+		/// once. Either every transfer succeeds or none does.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(T::WeightInfo::transfer_batch(transfers.len() as u32))]
+		pub fn transfer_batch(
+			origin: OriginFor<T>,
+			transfers: Vec<(<T::Lookup as StaticLookup>::Source, CurrencyIdOf<T>, BalanceOf<T>)>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			ensure!(
+				transfers.len() as u32 <= T::MaxTransferBatchSize::get(),
+				Error::<T>::TooManyTransfers
+			);
+			with_transaction_result(|| {
+				for (dest, currency_id, amount) in transfers {
+					let to = T::Lookup::lookup(dest)?;
+					<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &from, &to, amount)?;
+				}
+				Ok(())
+			})?;
+			Ok(().into())
+		}
+
+		/// Merge all the balances of the transactor into `dest`. Fails if the
+		/// transactor has balances locked or reserved, open orders, or
+		/// balances in a paused currency.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(T::WeightInfo::merge_account())]
+		pub fn merge_account(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let source = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+			let moved = Self::do_merge_account(&source, &dest)?;
+
+			Self::deposit_event(Event::AccountMerged(source, dest, moved));
+			Ok(().into())
+		}
+
+		/// update amount of account `who` under `currency_id`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		#[pallet::weight(T::WeightInfo::update_balance_non_native_currency())]
+		pub fn update_balance(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			amount: AmountOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let dest = T::Lookup::lookup(who)?;
+			<Self as Stp258CurrencyExtended<T::AccountId>>::update_balance(currency_id, &dest, amount)?;
+			Ok(().into())
+		}
+
+		/// Register `currency_id` as a stable currency the market may serp.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		#[pallet::weight(T::WeightInfo::register_stable_currency())]
+		pub fn register_stable_currency(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			info: StableCurrencyInfo<BalanceOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				currency_id != T::GetStp258NativeId::get(),
+				Error::<T>::NativeCurrencyCannotBeSerped
+			);
+			ensure!(
+				!StableCurrencies::<T>::contains_key(currency_id),
+				Error::<T>::StableCurrencyAlreadyRegistered
+			);
+			StableCurrencies::<T>::insert(currency_id, info);
+
+			Self::deposit_event(Event::StableCurrencyRegistered(currency_id, info));
+			Ok(().into())
+		}
+
+		/// Update the metadata of the registered stable currency `currency_id`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		#[pallet::weight(T::WeightInfo::update_stable_currency())]
+		pub fn update_stable_currency(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			info: StableCurrencyInfo<BalanceOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			StableCurrencies::<T>::try_mutate(currency_id, |maybe_info| -> DispatchResult {
+				let old_info = maybe_info.as_mut().ok_or(Error::<T>::StableCurrencyNotRegistered)?;
+				*old_info = info;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::StableCurrencyUpdated(currency_id, info));
+			Ok(().into())
+		}
+
+		/// Deregister the stable currency `currency_id`, so the market can no
+		/// longer serp it.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		#[pallet::weight(T::WeightInfo::deregister_stable_currency())]
+		pub fn deregister_stable_currency(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				StableCurrencies::<T>::contains_key(currency_id),
+				Error::<T>::StableCurrencyNotRegistered
+			);
+			StableCurrencies::<T>::remove(currency_id);
+			PidStates::<T>::remove(currency_id);
+
+			Self::deposit_event(Event::StableCurrencyDeregistered(currency_id));
+			Ok(().into())
+		}
+
+		/// Set the supply caps of the stable currency `currency_id`, or remove
+		/// them with `None`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		#[pallet::weight(T::WeightInfo::set_supply_caps())]
+		pub fn set_supply_caps(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			caps: Option<SupplyCaps<BalanceOf<T>>>,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				StableCurrencies::<T>::contains_key(currency_id),
+				Error::<T>::StableCurrencyNotRegistered
+			);
+			match caps {
+				Some(caps) => SupplyCapsOf::<T>::insert(currency_id, caps),
+				None => SupplyCapsOf::<T>::remove(currency_id),
+			}
+
+			Self::deposit_event(Event::SupplyCapsUpdated(currency_id, caps));
+			Ok(().into())
+		}
+
+		/// Set how newly issued stable currency is shared out, or leave it to
+		/// `Stp258Currency` with `None`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		#[pallet::weight(T::WeightInfo::set_seigniorage_shares())]
+		pub fn set_seigniorage_shares(
+			origin: OriginFor<T>,
+			shares: Option<SeigniorageShares>,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			match shares {
+				Some(shares) => {
+					ensure!(shares.is_valid(), Error::<T>::InvalidSeigniorageShares);
+					SeigniorageDistribution::<T>::put(shares);
+				}
+				None => SeigniorageDistribution::<T>::kill(),
+			}
+
+			Self::deposit_event(Event::SeigniorageSharesUpdated(shares));
+			Ok(().into())
+		}
+
+		/// Pause `currency_id`, so it can be neither serped nor transferred
+		/// nor have balances updated.
+		///
+		/// The dispatch origin of this call must be `PauseOrigin`.
+		#[pallet::weight(T::WeightInfo::pause())]
+		pub fn pause(origin: OriginFor<T>, currency_id: CurrencyIdOf<T>) -> DispatchResultWithPostInfo {
+			T::PauseOrigin::ensure_origin(origin)?;
+			PausedCurrencies::<T>::insert(currency_id, true);
+
+			Self::deposit_event(Event::CurrencyPaused(currency_id));
+			Ok(().into())
+		}
+
+		/// Unpause `currency_id`.
+		///
+		/// The dispatch origin of this call must be `PauseOrigin`.
+		#[pallet::weight(T::WeightInfo::unpause())]
+		pub fn unpause(origin: OriginFor<T>, currency_id: CurrencyIdOf<T>) -> DispatchResultWithPostInfo {
+			T::PauseOrigin::ensure_origin(origin)?;
+			PausedCurrencies::<T>::remove(currency_id);
+
+			Self::deposit_event(Event::CurrencyUnpaused(currency_id));
+			Ok(().into())
+		}
+
+		/// Pause every currency of the market.
+		///
+		/// The dispatch origin of this call must be `PauseOrigin`.
+		#[pallet::weight(T::WeightInfo::pause_all())]
+		pub fn pause_all(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			T::PauseOrigin::ensure_origin(origin)?;
+			MarketPaused::<T>::put(true);
+
+			Self::deposit_event(Event::MarketPaused);
+			Ok(().into())
+		}
+
+		/// Lift the pause of the whole market. Currencies paused one by one
+		/// stay paused.
+		///
+		/// The dispatch origin of this call must be `PauseOrigin`.
+		#[pallet::weight(T::WeightInfo::unpause_all())]
+		pub fn unpause_all(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			T::PauseOrigin::ensure_origin(origin)?;
+			MarketPaused::<T>::kill();
+
+			Self::deposit_event(Event::MarketUnpaused);
+			Ok(().into())
+		}
+
+		/// Expand the supply of the stable currency `currency_id` by
+		/// `expand_by` at `quote_price`.
+		///
+		/// The dispatch origin of this call must be `SerpOrigin`.
+		#[pallet::weight(T::WeightInfo::expand_supply())]
+		pub fn serp_up(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] expand_by: BalanceOf<T>,
+			#[pallet::compact] quote_price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::SerpOrigin::ensure_origin(origin)?;
+			<Self as SerpMarket<T::AccountId>>::expand_supply(
+				T::GetStp258NativeId::get(),
+				currency_id,
+				expand_by,
+				quote_price,
+			)?;
+			Ok(().into())
+		}
+
+		/// Contract the supply of the stable currency `currency_id` by
+		/// `contract_by` at `quote_price`.
+		///
+		/// The dispatch origin of this call must be `SerpOrigin`.
+		#[pallet::weight(T::WeightInfo::contract_supply())]
+		pub fn serp_down(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] contract_by: BalanceOf<T>,
+			#[pallet::compact] quote_price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::SerpOrigin::ensure_origin(origin)?;
+			<Self as SerpMarket<T::AccountId>>::contract_supply(
+				T::GetStp258NativeId::get(),
+				currency_id,
+				contract_by,
+				quote_price,
+			)?;
+			Ok(().into())
+		}
+
+		/// Bid `amount` of the stable currency `currency_id` on its
+		/// contraction auction. The bid is reserved and settled at the end of
+		/// the block at the auction price of that block, any part beyond the
+		/// auction target being returned.
+		///
+		/// Bids are not settled at a common clearing price: a bidder placing
+		/// later, as the price falls towards `floor_price`, pays less per unit
+		/// at the risk of the auction reaching its target first.
+		///
+		/// The dispatch origin for this call must be `Signed` by the bidder.
+		#[pallet::weight(T::WeightInfo::bid())]
+		pub fn bid(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(currency_id)?;
+			ensure!(Auctions::<T>::contains_key(currency_id), Error::<T>::AuctionNotFound);
+			let mut bids = AuctionBids::<T>::get(currency_id);
+			ensure!(
+				(bids.len() as u32) < T::MaxAuctionBids::get(),
+				Error::<T>::TooManyBids
+			);
+
+			<Self as Stp258CurrencyNamedReservable<T::AccountId>>::reserve_named(
+				&AUCTION_RESERVE_ID,
+				currency_id,
+				&who,
+				amount,
+			)?;
+			bids.push((who.clone(), amount));
+			AuctionBids::<T>::insert(currency_id, bids);
+			Self::deposit_event(Event::BidPlaced(currency_id, who, amount));
+			Ok(().into())
+		}
+
+		/// Burn up to `amount` of the stable currency `currency_id` for a bond
+		/// worth `BondPremium` more, redeemed first-in first-out during later
+		/// supply expansions.
+		///
+		/// The dispatch origin for this call must be `Signed` by the buyer.
+		#[pallet::weight(T::WeightInfo::buy_bonds())]
+		pub fn buy_bonds(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(currency_id)?;
+			let offered = Self::bond_offers(currency_id);
+			let paid = amount.min(offered);
+			ensure!(!paid.is_zero(), Error::<T>::NoBondOffer);
+			let tally = Self::ensure_within_supply_caps(currency_id, SupplyAdjustment::Contract(paid))?;
+
+			<Self as Stp258Currency<T::AccountId>>::withdraw(currency_id, &who, paid)?;
+			let quote_price = Self::bond_offer_quotes(currency_id);
+			if offered == paid {
+				BondOffers::<T>::remove(currency_id);
+				BondOfferQuotes::<T>::remove(currency_id);
+			} else {
+				BondOffers::<T>::insert(currency_id, offered.saturating_sub(paid));
+			}
+
+			let face_value = paid.saturating_add(T::BondPremium::get().mul_floor(paid));
+			let (head, bond_id) = Self::bond_queue(currency_id);
+			Bonds::<T>::insert(
+				currency_id,
+				bond_id,
+				SerpBond {
+					owner: who.clone(),
+					face_value,
+					expires: frame_system::Module::<T>::block_number().saturating_add(T::BondDuration::get()),
+				},
+			);
+			BondQueue::<T>::insert(currency_id, (head, bond_id.saturating_add(1)));
+			SupplyAdjustmentTallies::<T>::insert(currency_id, tally);
+			Self::record_supply_adjustment(currency_id, SupplyAdjustment::Contract(paid), quote_price);
+			Self::deposit_event(Event::BondIssued(currency_id, bond_id, who, paid, face_value));
+			Self::deposit_event(Event::SerpedDownSupply(currency_id, paid));
+			Ok(().into())
+		}
+
+		/// Add up to `native_amount` of native currency and `stable_amount` of
+		/// `stable_currency_id` to their pool, at the pool ratio, for at
+		/// least `min_shares` liquidity shares. The first liquidity sets the
+		/// ratio and is issued one share per native currency unit.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// liquidity provider.
+		#[pallet::weight(T::WeightInfo::add_liquidity())]
+		pub fn add_liquidity(
+			origin: OriginFor<T>,
+			stable_currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] native_amount: BalanceOf<T>,
+			#[pallet::compact] stable_amount: BalanceOf<T>,
+			#[pallet::compact] min_shares: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let pool_id = Self::pool_id(stable_currency_id)?;
+			let pool = Self::pools(pool_id);
+
+			let (native_in, stable_in, shares) = if pool.total_shares.is_zero() {
+				(native_amount, stable_amount, native_amount)
+			} else {
+				let stable_for_native = Self::mul_div(native_amount, pool.stable_reserve, pool.native_reserve)?;
+				if stable_for_native <= stable_amount {
+					let shares = Self::mul_div(native_amount, pool.total_shares, pool.native_reserve)?;
+					(native_amount, stable_for_native, shares)
+				} else {
+					let native_for_stable = Self::mul_div(stable_amount, pool.native_reserve, pool.stable_reserve)?;
+					let shares = Self::mul_div(stable_amount, pool.total_shares, pool.stable_reserve)?;
+					(native_for_stable, stable_amount, shares)
+				}
+			};
+			ensure!(
+				!native_in.is_zero() && !stable_in.is_zero() && !shares.is_zero(),
+				Error::<T>::InvalidLiquidityAmount
+			);
+			ensure!(shares >= min_shares, Error::<T>::SlippageExceeded);
+
+			let pool_account = Self::pool_account();
+			with_transaction_result(|| {
+				<Self as Stp258Currency<T::AccountId>>::transfer(pool_id.0, &who, &pool_account, native_in)?;
+				<Self as Stp258Currency<T::AccountId>>::transfer(pool_id.1, &who, &pool_account, stable_in)
+			})?;
+			Pools::<T>::insert(
+				pool_id,
+				LiquidityPool {
+					native_reserve: pool.native_reserve.saturating_add(native_in),
+					stable_reserve: pool.stable_reserve.saturating_add(stable_in),
+					total_shares: pool.total_shares.saturating_add(shares),
+				},
+			);
+			LiquidityShares::<T>::mutate(pool_id, &who, |owned| *owned = owned.saturating_add(shares));
+
+			Self::deposit_event(Event::LiquidityAdded(who, stable_currency_id, native_in, stable_in, shares));
+			Ok(().into())
+		}
+
+		/// Redeem `shares` of the pool of `stable_currency_id` for at least
+		/// `min_native_amount` of native currency and `min_stable_amount` of
+		/// stable currency.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// liquidity provider.
+		#[pallet::weight(T::WeightInfo::remove_liquidity())]
+		pub fn remove_liquidity(
+			origin: OriginFor<T>,
+			stable_currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] shares: BalanceOf<T>,
+			#[pallet::compact] min_native_amount: BalanceOf<T>,
+			#[pallet::compact] min_stable_amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let pool_id = Self::pool_id(stable_currency_id)?;
+			let pool = Self::pools(pool_id);
+			let owned = Self::liquidity_shares(pool_id, &who);
+			ensure!(!shares.is_zero(), Error::<T>::InvalidLiquidityAmount);
+			ensure!(shares <= owned, Error::<T>::InsufficientShares);
+
+			let native_out = Self::mul_div(shares, pool.native_reserve, pool.total_shares)?;
+			let stable_out = Self::mul_div(shares, pool.stable_reserve, pool.total_shares)?;
+			ensure!(
+				native_out >= min_native_amount && stable_out >= min_stable_amount,
+				Error::<T>::SlippageExceeded
+			);
+
+			let pool_account = Self::pool_account();
+			with_transaction_result(|| {
+				<Self as Stp258Currency<T::AccountId>>::transfer(pool_id.0, &pool_account, &who, native_out)?;
+				<Self as Stp258Currency<T::AccountId>>::transfer(pool_id.1, &pool_account, &who, stable_out)
+			})?;
+			Pools::<T>::insert(
+				pool_id,
+				LiquidityPool {
+					native_reserve: pool.native_reserve.saturating_sub(native_out),
+					stable_reserve: pool.stable_reserve.saturating_sub(stable_out),
+					total_shares: pool.total_shares.saturating_sub(shares),
+				},
+			);
+			if owned == shares {
+				LiquidityShares::<T>::remove(pool_id, &who);
+			} else {
+				LiquidityShares::<T>::insert(pool_id, &who, owned.saturating_sub(shares));
+			}
+
+			Self::deposit_event(Event::LiquidityRemoved(who, stable_currency_id, native_out, stable_out, shares));
+			Ok(().into())
+		}
+
+		/// Swap exactly `amount_in` of `currency_in` for at least
+		/// `min_amount_out` of `currency_out`, one of them being the native
+		/// currency and the other a stable currency.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// swapper.
+		#[pallet::weight(T::WeightInfo::swap_exact_in())]
+		pub fn swap_exact_in(
+			origin: OriginFor<T>,
+			currency_in: CurrencyIdOf<T>,
+			currency_out: CurrencyIdOf<T>,
+			#[pallet::compact] amount_in: BalanceOf<T>,
+			#[pallet::compact] min_amount_out: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let (reserve_in, reserve_out) = Self::swap_reserves(currency_in, currency_out)?;
+			let amount_out = Self::amount_out(amount_in, reserve_in, reserve_out)?;
+			ensure!(amount_out >= min_amount_out, Error::<T>::SlippageExceeded);
+			Self::swap(&who, currency_in, currency_out, amount_in, amount_out)?;
+			Ok(().into())
+		}
+
+		/// Swap at most `max_amount_in` of `currency_in` for exactly
+		/// `amount_out` of `currency_out`, one of them being the native
+		/// currency and the other a stable currency.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// swapper.
+		#[pallet::weight(T::WeightInfo::swap_exact_out())]
+		pub fn swap_exact_out(
+			origin: OriginFor<T>,
+			currency_in: CurrencyIdOf<T>,
+			currency_out: CurrencyIdOf<T>,
+			#[pallet::compact] amount_out: BalanceOf<T>,
+			#[pallet::compact] max_amount_in: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let (reserve_in, reserve_out) = Self::swap_reserves(currency_in, currency_out)?;
+			let amount_in = Self::amount_in(amount_out, reserve_in, reserve_out)?;
+			ensure!(amount_in <= max_amount_in, Error::<T>::SlippageExceeded);
+			Self::swap(&who, currency_in, currency_out, amount_in, amount_out)?;
+			Ok(().into())
+		}
+
+		/// Place a limit order to buy or sell `amount` of native currency for
+		/// the stable currency `currency_id` at `price` per base unit of
+		/// native currency or better. The funds of the order are reserved
+		/// until it is filled or cancelled; crossing orders are matched at
+		/// the end of the block in price-time priority, at the price of the
+		/// older order. Each side of the book holds up to `MaxBookOrders`
+		/// orders.
+		///
+		/// The dispatch origin for this call must be `Signed` by the trader.
+		#[pallet::weight(T::WeightInfo::place_order(T::MaxBookOrders::get()))]
+		pub fn place_order(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			side: OrderSide,
+			#[pallet::compact] price: BalanceOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(currency_id)?;
+			ensure!(
+				StableCurrencies::<T>::contains_key(currency_id),
+				Error::<T>::StableCurrencyNotRegistered
+			);
+			let mut open_orders = Self::open_orders(&who);
+			ensure!(
+				(open_orders.len() as u32) < T::MaxOpenOrders::get(),
+				Error::<T>::TooManyOpenOrders
+			);
+
+			let cost = Self::order_cost(amount, price)?;
+			ensure!(!cost.is_zero(), Error::<T>::InvalidOrder);
+			let (reserve_currency_id, reserved) = match side {
+				OrderSide::Buy => (currency_id, cost),
+				OrderSide::Sell => (T::GetStp258NativeId::get(), amount),
+			};
+			let book_len = match side {
+				OrderSide::Buy => Self::buy_orders(currency_id).len(),
+				OrderSide::Sell => Self::sell_orders(currency_id).len(),
+			};
+			ensure!((book_len as u32) < T::MaxBookOrders::get(), Error::<T>::OrderBookFull);
+			<Self as Stp258CurrencyReservable<T::AccountId>>::reserve(reserve_currency_id, &who, reserved)?;
+
+			let order_id = Self::next_order_id();
+			NextOrderId::<T>::put(order_id.saturating_add(1));
+			Orders::<T>::insert(
+				currency_id,
+				order_id,
+				Order {
+					owner: who.clone(),
+					side,
+					price,
+					amount,
+					reserved,
+				},
+			);
+			match side {
+				OrderSide::Buy => {
+					let mut book = Self::buy_orders(currency_id);
+					let index = book.iter().position(|&(p, _)| p < price).unwrap_or_else(|| book.len());
+					book.insert(index, (price, order_id));
+					Self::put_buy_orders(currency_id, book);
+				}
+				OrderSide::Sell => SellOrders::<T>::mutate(currency_id, |book| {
+					let index = book.iter().position(|&(p, _)| p > price).unwrap_or_else(|| book.len());
+					book.insert(index, (price, order_id));
+				}),
+			}
+			open_orders.push((currency_id, order_id));
+			OpenOrders::<T>::insert(&who, open_orders);
+
+			Self::deposit_event(Event::OrderPlaced(currency_id, order_id, who, side, price, amount));
+			Ok(().into())
+		}
+
+		/// Cancel the open order `order_id` of the stable currency
+		/// `currency_id`, unreserving its remaining funds.
+		///
+		/// The dispatch origin for this call must be `Signed` by the owner of
+		/// the order.
+		#[pallet::weight(T::WeightInfo::cancel_order(T::MaxBookOrders::get()))]
+		pub fn cancel_order(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			order_id: OrderId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let order = Self::orders(currency_id, order_id)
+				.filter(|order| order.owner == who)
+				.ok_or(Error::<T>::OrderNotFound)?;
+			match order.side {
+				OrderSide::Buy => {
+					let mut book = Self::buy_orders(currency_id);
+					book.retain(|&(_, id)| id != order_id);
+					Self::put_buy_orders(currency_id, book);
+				}
+				OrderSide::Sell => SellOrders::<T>::mutate(currency_id, |book| book.retain(|&(_, id)| id != order_id)),
+			}
+			Self::close_order(currency_id, order_id, &order);
+			Self::deposit_event(Event::OrderCancelled(currency_id, order_id, who));
+			Ok(().into())
+		}
+
+		/// Unlock the balance of `currency_id` the vesting schedules of the
+		/// caller have vested so far.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// vesting account.
+		#[pallet::weight(T::WeightInfo::claim(T::MaxVestingSchedules::get()))]
+		pub fn claim(origin: OriginFor<T>, currency_id: CurrencyIdOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let still_locked = Self::update_vesting_lock(&who, currency_id)?;
+			Self::deposit_event(Event::VestingClaimed(currency_id, who, still_locked));
+			Ok(().into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The balances of `who` in the native currency and every registered
+	/// stable currency.
+	pub fn all_balances(who: &T::AccountId) -> Vec<(CurrencyIdOf<T>, AccountBalance<BalanceOf<T>>)> {
+		sp_std::iter::once(T::GetStp258NativeId::get())
+			.chain(StableCurrencies::<T>::iter().map(|(currency_id, _)| currency_id))
+			.map(|currency_id| {
+				let balance = AccountBalance {
+					free: <Self as Stp258Currency<T::AccountId>>::free_balance(currency_id, who),
+					reserved: <Self as Stp258CurrencyReservable<T::AccountId>>::reserved_balance(currency_id, who),
+				};
+				(currency_id, balance)
+			})
+			.collect()
+	}
+
+	/// The largest amount of `currency_id` `who` can withdraw, whichever
+	/// pallet set the locks freezing the rest of its free balance.
+	pub fn transferable_balance(currency_id: CurrencyIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+		let can_withdraw = |amount: BalanceOf<T>| {
+			<Self as Stp258Currency<T::AccountId>>::ensure_can_withdraw(currency_id, who, amount).is_ok()
+		};
+		let free = <Self as Stp258Currency<T::AccountId>>::free_balance(currency_id, who);
+		if can_withdraw(free) {
+			return free;
+		}
+		// withdrawable amounts are those up to the frozen part of `free`
+		let (mut low, mut high) = (BalanceOf::<T>::zero(), free);
+		while high.saturating_sub(low) > One::one() {
+			let mid = low.saturating_add(high.saturating_sub(low) / 2u32.into());
+			if can_withdraw(mid) {
+				low = mid;
+			} else {
+				high = mid;
+			}
+		}
+		low
+	}
+
+	/// Transfer `amount` under `currency_id` from `from` to `to`, failing if
+	/// it would reap `from`.
+	fn transfer_keep_alive_under(
+		currency_id: CurrencyIdOf<T>,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		if amount.is_zero() || from == to {
+			return Ok(());
+		}
+		Self::ensure_not_paused(currency_id)?;
+		if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::transfer_keep_alive(from, to, amount)?;
+		} else {
+			// non-native currencies have no keep-alive transfer, so the
+			// remaining balance is checked against their existential deposit
+			let remaining = <Self as Stp258Currency<T::AccountId>>::total_balance(currency_id, from)
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::BalanceTooLow)?;
+			ensure!(
+				!remaining.is_zero()
+					&& remaining >= <Self as Stp258Currency<T::AccountId>>::minimum_balance(currency_id),
+				Error::<T>::KeepAlive
+			);
+			T::Stp258Currency::transfer(currency_id, from, to, amount)?;
+		}
+		Self::deposit_event(Event::Transferred(currency_id, from.clone(), to.clone(), amount));
+		Ok(())
+	}
+
+	/// Ensure `who` has neither balances locked or reserved, open orders nor
+	/// balances in a paused currency among the native currency and the
+	/// registered stable currencies, returning their balances merging it
+	/// would move.
+	fn ensure_mergeable(who: &T::AccountId) -> result::Result<Vec<(CurrencyIdOf<T>, BalanceOf<T>)>, DispatchError> {
+		ensure!(
+			NamedReserves::<T>::iter_prefix(who).next().is_none(),
+			Error::<T>::AccountHasReserves
+		);
+		ensure!(Self::open_orders(who).is_empty(), Error::<T>::AccountHasOpenOrders);
+		let mut moved = vec![];
+		for (currency_id, balance) in Self::all_balances(who) {
+			if balance.free.is_zero() && balance.reserved.is_zero() {
+				continue;
+			}
+			Self::ensure_not_paused(currency_id)?;
+			ensure!(
+				<Self as Stp258Currency<T::AccountId>>::ensure_can_withdraw(currency_id, who, balance.free).is_ok(),
+				Error::<T>::AccountHasLocks
+			);
+			ensure!(balance.reserved.is_zero(), Error::<T>::AccountHasReserves);
+			if !balance.free.is_zero() {
+				moved.push((currency_id, balance.free));
+			}
+		}
+		Ok(moved)
+	}
+
+	/// Merge all the balances of `source` into `dest`, returning the native
+	/// and registered stable currency balances moved.
+	fn do_merge_account(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+	) -> result::Result<Vec<(CurrencyIdOf<T>, BalanceOf<T>)>, DispatchError> {
+		let moved = Self::ensure_mergeable(source)?;
+		with_transaction_result(|| {
+			// transfer non-native free to dest
+			T::Stp258Currency::merge_account(source, dest)?;
+
+			// transfer all native free to dest
+			T::Stp258Native::transfer(source, dest, T::Stp258Native::free_balance(source))
+		})?;
+		Ok(moved)
+	}
+
+	/// The past supply adjustments of `currency_id`, oldest first.
+	pub fn supply_history(currency_id: CurrencyIdOf<T>) -> Vec<SupplyAdjustmentRecord<T::BlockNumber, BalanceOf<T>>> {
+		let capacity = T::MaxSupplyHistory::get();
+		if capacity.is_zero() {
+			return vec![];
+		}
+		let count = Self::supply_history_count(currency_id);
+		let first = count.saturating_sub(capacity);
+		(first..count)
+			.filter_map(|index| SupplyHistory::<T>::get(currency_id, index % capacity))
+			.collect()
+	}
+
+	/// Record `adjustment` of `currency_id` in its supply history,
+	/// overwriting the oldest record once the history is full.
+	fn record_supply_adjustment(
+		currency_id: CurrencyIdOf<T>,
+		adjustment: SupplyAdjustment<BalanceOf<T>>,
+		quote_price: BalanceOf<T>,
+	) {
+		let capacity = T::MaxSupplyHistory::get();
+		if capacity.is_zero() {
+			return;
+		}
+		let count = Self::supply_history_count(currency_id);
+		let record = SupplyAdjustmentRecord {
+			block_number: frame_system::Module::<T>::block_number(),
+			adjustment,
+			quote_price,
+			total_issuance: <Self as Stp258Currency<T::AccountId>>::total_issuance(currency_id),
+		};
+		SupplyHistory::<T>::insert(currency_id, count % capacity, record);
+		SupplyHistoryCount::<T>::insert(currency_id, count.saturating_add(1));
+	}
+
+	/// Add `amount` to the reserve of `who` under `id`, or take it away when
+	/// `increase` is false.
+	fn mutate_named_reserve(
+		id: &ReserveIdentifier,
+		currency_id: CurrencyIdOf<T>,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+		increase: bool,
+	) {
+		if amount.is_zero() {
+			return;
+		}
+		NamedReserves::<T>::mutate_exists(who, (currency_id, *id), |maybe_reserved| {
+			let reserved = maybe_reserved.unwrap_or_default();
+			let reserved = if increase {
+				reserved.saturating_add(amount)
+			} else {
+				reserved.saturating_sub(amount)
+			};
+			*maybe_reserved = if reserved.is_zero() { None } else { Some(reserved) };
+		});
+	}
+
+	/// The balance of `who` reserved under any name in `currency_id`.
+	fn named_reserved_balance(currency_id: CurrencyIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+		NamedReserves::<T>::iter_prefix(who)
+			.filter(|((reserve_currency_id, _), _)| *reserve_currency_id == currency_id)
+			.fold(Zero::zero(), |total: BalanceOf<T>, (_, reserved)| total.saturating_add(reserved))
+	}
+
+	/// The balance of `who` reserved in `currency_id` outside any named
+	/// reserve, the only part the unnamed reserve operations may move.
+	fn unnamed_reserved_balance(currency_id: CurrencyIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+		<Self as Stp258CurrencyReservable<T::AccountId>>::reserved_balance(currency_id, who)
+			.saturating_sub(Self::named_reserved_balance(currency_id, who))
+	}
+
+	/// Slash up to `value` of the reserved balance of `who`, named or not,
+	/// returning the amount that could not be slashed.
+	fn slash_any_reserved(currency_id: CurrencyIdOf<T>, who: &T::AccountId, value: BalanceOf<T>) -> BalanceOf<T> {
+		let gap = if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::slash_reserved(who, value)
+		} else {
+			T::Stp258Currency::slash_reserved(currency_id, who, value)
+		};
+		let slashed = value.saturating_sub(gap);
+		if !slashed.is_zero() {
+			Self::deposit_event(Event::ReserveSlashed(currency_id, who.clone(), slashed));
+		}
+		gap
+	}
+
+	/// Unreserve up to `value` of the reserved balance of `who`, named or
+	/// not, returning the amount that could not be unreserved.
+	fn unreserve_any(currency_id: CurrencyIdOf<T>, who: &T::AccountId, value: BalanceOf<T>) -> BalanceOf<T> {
+		let gap = if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::unreserve(who, value)
+		} else {
+			T::Stp258Currency::unreserve(currency_id, who, value)
+		};
+		let unreserved = value.saturating_sub(gap);
+		if !unreserved.is_zero() {
+			Self::deposit_event(Event::Unreserved(currency_id, who.clone(), unreserved));
+		}
+		gap
+	}
+
+	/// Move up to `value` of the reserved balance of `slashed`, named or
+	/// not, to `beneficiary`, returning the amount that could not be moved.
+	fn repatriate_any_reserved(
+		currency_id: CurrencyIdOf<T>,
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: BalanceOf<T>,
+		status: BalanceStatus,
+	) -> result::Result<BalanceOf<T>, DispatchError> {
+		let gap = if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::repatriate_reserved(slashed, beneficiary, value, status)?
+		} else {
+			T::Stp258Currency::repatriate_reserved(currency_id, slashed, beneficiary, value, status)?
+		};
+		let repatriated = value.saturating_sub(gap);
+		if !repatriated.is_zero() {
+			Self::deposit_event(Event::ReserveRepatriated(
+				currency_id,
+				slashed.clone(),
+				beneficiary.clone(),
+				repatriated,
+				status,
+			));
+		}
+		Ok(gap)
+	}
+
+	/// Adjust the supply of every enabled stable currency towards its peg
+	/// every `AdjustmentFrequency` blocks.
+	fn serp_stable_currencies(now: T::BlockNumber) -> Weight {
+		let frequency = T::AdjustmentFrequency::get();
+		if frequency.is_zero() || !(now % frequency).is_zero() {
+			return T::WeightInfo::on_initialize(0);
+		}
+
+		let stable_currencies = StableCurrencies::<T>::iter().collect::<Vec<_>>();
+		for (currency_id, info) in stable_currencies.iter() {
+			if !info.enabled {
+				continue;
+			}
+			if let Err(e) = Self::serp_to_peg(*currency_id, info) {
+				native::warn!("💸 Unable to serp currency {:?}: {:?}", currency_id, e);
+			}
+		}
+		T::WeightInfo::on_initialize(stable_currencies.len() as u32)
+	}
+
+	/// The weight `on_finalize` may use, settling up to `MaxAuctionBids`
+	/// bids on every open auction and making up to `MaxOrderMatches` matches
+	/// on every order book with buy orders, as counted by `AuctionCount` and
+	/// `BuyOrderBookCount`.
+	fn on_finalize_weight() -> Weight {
+		let auctions = Weight::from(Self::auction_count());
+		let books = Weight::from(Self::buy_order_book_count());
+		T::WeightInfo::settle_auction(T::MaxAuctionBids::get())
+			.saturating_mul(auctions)
+			.saturating_add(T::WeightInfo::match_orders(T::MaxOrderMatches::get()).saturating_mul(books))
+	}
+
+	/// Remove every timed lock expiring at `now`, returning the number of
+	/// entries processed.
+	fn lift_expired_locks(now: T::BlockNumber) -> u32 {
+		let expiring = ExpiringLocks::<T>::take(now);
+		for (lock_id, currency_id, who) in expiring.iter() {
+			if LockExpiries::<T>::get(who, (*currency_id, *lock_id)) != Some(now) {
+				continue;
+			}
+			if let Err(e) = <Self as Stp258CurrencyLockable<T::AccountId>>::remove_lock(*lock_id, *currency_id, who) {
+				native::warn!("💸 Unable to lift lock {:?} of {:?}: {:?}", lock_id, who, e);
+			}
+		}
+		expiring.len() as u32
+	}
+
+	/// Add `schedule` to the vesting schedules of `who` in `currency_id`.
+	pub fn add_vesting_schedule(
+		who: &T::AccountId,
+		currency_id: CurrencyIdOf<T>,
+		schedule: VestingSchedule<T::BlockNumber, BalanceOf<T>>,
+	) -> DispatchResult {
+		ensure!(schedule.is_valid(), Error::<T>::InvalidVestingSchedule);
+		VestingSchedules::<T>::try_mutate(who, currency_id, |schedules| -> DispatchResult {
+			ensure!(
+				(schedules.len() as u32) < T::MaxVestingSchedules::get(),
+				Error::<T>::TooManyVestingSchedules
+			);
+			schedules.push(schedule);
+			Ok(())
+		})?;
+		Self::update_vesting_lock(who, currency_id)?;
+		Self::deposit_event(Event::VestingScheduleAdded(currency_id, who.clone(), schedule));
+		Ok(())
+	}
+
+	/// Drop the fully vested schedules of `who` in `currency_id` and lock
+	/// what the rest still lock, returning the locked amount.
+	fn update_vesting_lock(
+		who: &T::AccountId,
+		currency_id: CurrencyIdOf<T>,
+	) -> result::Result<BalanceOf<T>, DispatchError> {
+		let now = frame_system::Module::<T>::block_number();
+		let mut schedules = VestingSchedules::<T>::get(who, currency_id);
+		if schedules.is_empty() {
+			return Ok(Zero::zero());
+		}
+		schedules.retain(|schedule| !schedule.locked_at(now).is_zero());
+		let locked = schedules
+			.iter()
+			.fold(BalanceOf::<T>::zero(), |locked, schedule| locked.saturating_add(schedule.locked_at(now)));
+
+		if locked.is_zero() {
+			VestingSchedules::<T>::remove(who, currency_id);
+			<Self as Stp258CurrencyLockable<T::AccountId>>::remove_lock(VESTING_LOCK_ID, currency_id, who)?;
+		} else {
+			VestingSchedules::<T>::insert(who, currency_id, schedules);
+			<Self as Stp258CurrencyLockable<T::AccountId>>::set_lock(VESTING_LOCK_ID, currency_id, who, locked)?;
+		}
+		Ok(locked)
+	}
+
+	/// Vest the `proceeds` of `SerperAccount` from expanding `currency_id`
+	/// under `ExpansionVesting`. With no schedule slot left, the proceeds are
+	/// merged into the newest schedule, which keeps its start, cliff and
+	/// duration.
+	fn vest_expansion_proceeds(currency_id: CurrencyIdOf<T>, proceeds: BalanceOf<T>) -> DispatchResult {
+		let (cliff, duration) = match T::ExpansionVesting::get() {
+			Some(terms) => terms,
+			None => return Ok(()),
+		};
+		if proceeds.is_zero() {
+			return Ok(());
+		}
+		let serper = T::SerperAccount::get();
+		let now = frame_system::Module::<T>::block_number();
+		Self::update_vesting_lock(&serper, currency_id)?;
+
+		let mut schedules = VestingSchedules::<T>::get(&serper, currency_id);
+		if (schedules.len() as u32) < T::MaxVestingSchedules::get() {
+			return Self::add_vesting_schedule(
+				&serper,
+				currency_id,
+				VestingSchedule {
+					start: now,
+					cliff,
+					duration,
+					total: proceeds,
+				},
+			);
+		}
+		let newest = schedules.last_mut().ok_or(Error::<T>::TooManyVestingSchedules)?;
+		newest.total = newest.total.saturating_add(proceeds);
+		let schedule = *newest;
+		VestingSchedules::<T>::insert(&serper, currency_id, schedules);
+		Self::update_vesting_lock(&serper, currency_id)?;
+		Self::deposit_event(Event::VestingScheduleAdded(currency_id, serper, schedule));
+		Ok(())
+	}
 
-pub use module::*;
+	/// Start a contraction auction raising `contract_by` of `currency_id`,
+	/// priced around `quote_price`.
+	fn start_auction(
+		currency_id: CurrencyIdOf<T>,
+		contract_by: BalanceOf<T>,
+		quote_price: BalanceOf<T>,
+	) -> DispatchResult {
+		ensure!(!Auctions::<T>::contains_key(currency_id), Error::<T>::AuctionInProgress);
+		let start = frame_system::Module::<T>::block_number();
+		let auction = ContractionAuction {
+			start,
+			end: start.saturating_add(T::AuctionDuration::get()),
+			target: contract_by,
+			raised: Zero::zero(),
+			start_price: quote_price.saturating_add(T::AuctionStartPremium::get().mul_floor(quote_price)),
+			floor_price: quote_price.saturating_sub(T::AuctionFloorDiscount::get().mul_floor(quote_price)),
+			quote_price,
+		};
+		Auctions::<T>::insert(currency_id, auction);
+		AuctionCount::<T>::mutate(|count| *count = count.saturating_add(1));
+		Self::deposit_event(Event::AuctionStarted(currency_id, auction));
+		Ok(())
+	}
 
-#[frame_support::pallet]
-pub mod module {
-	use super::*;
+	/// Settle the bids on the auction of `currency_id` at the price of
+	/// block `now`, burning the stable currency raised and paying out newly
+	/// issued native currency, and charging the amount burned to its supply
+	/// adjustment tally. Bids pay only for the whole base units they
+	/// receive, the rest being returned. Closes the auction once it reaches
+	/// its target or its end.
+	fn settle_auction(
+		currency_id: CurrencyIdOf<T>,
+		mut auction: ContractionAuction<T::BlockNumber, BalanceOf<T>>,
+		now: T::BlockNumber,
+	) {
+		let native_currency_id = T::GetStp258NativeId::get();
+		let native_unit: u128 = Self::base_unit(native_currency_id).max(One::one()).unique_saturated_into();
+		let price: u128 = auction.price_at(now).max(One::one()).unique_saturated_into();
+		let mut burned = BalanceOf::<T>::zero();
+
+		for (who, amount) in AuctionBids::<T>::take(currency_id) {
+			let offered: u128 = amount.min(auction.target.saturating_sub(auction.raised)).unique_saturated_into();
+			let received: u128 = offered.saturating_mul(native_unit) / price;
+			let paid = BalanceOf::<T>::unique_saturated_from(
+				received.saturating_mul(price).saturating_add(native_unit - 1) / native_unit,
+			);
+			let received = BalanceOf::<T>::unique_saturated_from(received);
+			let settled = !received.is_zero()
+				&& <Self as Stp258Currency<T::AccountId>>::deposit(native_currency_id, &who, received).is_ok();
+			let paid = if settled {
+				<Self as Stp258CurrencyNamedReservable<T::AccountId>>::slash_reserved_named(
+					&AUCTION_RESERVE_ID,
+					currency_id,
+					&who,
+					paid,
+				);
+				auction.raised = auction.raised.saturating_add(paid);
+				burned = burned.saturating_add(paid);
+				Self::deposit_event(Event::BidSettled(currency_id, who.clone(), paid, received));
+				paid
+			} else {
+				Zero::zero()
+			};
+			<Self as Stp258CurrencyNamedReservable<T::AccountId>>::unreserve_named(
+				&AUCTION_RESERVE_ID,
+				currency_id,
+				&who,
+				amount.saturating_sub(paid),
+			);
+		}
 
-	pub trait WeightInfo {
-		fn transfer_non_native_currency() -> Weight;
-		fn transfer_native_currency() -> Weight;
-		fn update_balance_non_native_currency() -> Weight;
-		fn update_balance_native_currency_creating() -> Weight;
-		fn update_balance_native_currency_killing() -> Weight;
+		if !burned.is_zero() {
+			let (tally, ..) = Self::tally_supply_adjustment(currency_id, SupplyAdjustment::Contract(burned));
+			SupplyAdjustmentTallies::<T>::insert(currency_id, tally);
+			Self::deposit_event(Event::SerpedDownSupply(currency_id, burned));
+		}
+		if auction.raised >= auction.target || now >= auction.end {
+			Auctions::<T>::remove(currency_id);
+			AuctionCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			if !auction.raised.is_zero() {
+				Self::record_supply_adjustment(
+					currency_id,
+					SupplyAdjustment::Contract(auction.raised),
+					auction.quote_price,
+				);
+			}
+			Self::deposit_event(Event::AuctionClosed(currency_id, auction.raised));
+		} else {
+			Auctions::<T>::insert(currency_id, auction);
+		}
 	}
 
-	pub(crate) type BalanceOf<T> =
-		<<T as Config>::Stp258Currency as Stp258Currency<<T as frame_system::Config>::AccountId>>::Balance;
-	pub(crate) type CurrencyIdOf<T> =
-		<<T as Config>::Stp258Currency as Stp258Currency<<T as frame_system::Config>::AccountId>>::CurrencyId;
-	pub(crate) type AmountOf<T> =
-		<<T as Config>::Stp258Currency as Stp258CurrencyExtended<<T as frame_system::Config>::AccountId>>::Amount;
+	/// Redeem up to `amount` of the bonds of `currency_id` in issue order,
+	/// dropping expired bonds, returning the amount redeemed.
+	fn redeem_bonds(currency_id: CurrencyIdOf<T>, amount: BalanceOf<T>) -> BalanceOf<T> {
+		let now = frame_system::Module::<T>::block_number();
+		let (mut head, next) = Self::bond_queue(currency_id);
+		let mut redeemed = BalanceOf::<T>::zero();
+		let mut processed = 0u32;
+
+		while head < next && redeemed < amount && processed < T::MaxBondRedemptions::get() {
+			processed += 1;
+			let mut bond = match Self::bonds(currency_id, head) {
+				Some(bond) => bond,
+				None => {
+					head += 1;
+					continue;
+				}
+			};
+			if bond.expires <= now {
+				Bonds::<T>::remove(currency_id, head);
+				Self::deposit_event(Event::BondExpired(currency_id, head, bond.owner, bond.face_value));
+				head += 1;
+				continue;
+			}
 
-	#[pallet::config]
-	pub trait Config: frame_system::Config {
-		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+			let payout = bond.face_value.min(amount.saturating_sub(redeemed));
+			if let Err(e) = <Self as Stp258Currency<T::AccountId>>::deposit(currency_id, &bond.owner, payout) {
+				native::warn!("💸 Unable to redeem bond {:?} of {:?}: {:?}", head, currency_id, e);
+				break;
+			}
+			redeemed = redeemed.saturating_add(payout);
+			bond.face_value = bond.face_value.saturating_sub(payout);
+			Self::deposit_event(Event::BondRedeemed(currency_id, head, bond.owner.clone(), payout));
+			if bond.face_value.is_zero() {
+				Bonds::<T>::remove(currency_id, head);
+				head += 1;
+			} else {
+				Bonds::<T>::insert(currency_id, head, bond);
+			}
+		}
 
-		type Stp258Currency: MergeAccount<Self::AccountId>
-			+ Stp258CurrencyExtended<Self::AccountId>
-			+ Stp258CurrencyLockable<Self::AccountId>
-			+ Stp258CurrencyReservable<Self::AccountId>
-			+ SerpMarket<Self::AccountId>;
+		BondQueue::<T>::insert(currency_id, (head, next));
+		redeemed
+	}
+
+	/// Issue `amount` of `currency_id` to the seigniorage recipients by
+	/// `shares`, vesting the serpers' share, returning the amount issued. A
+	/// share that would leave its recipient below the existential deposit is
+	/// added to the reserve buffer payout, and left unissued if that is dust
+	/// as well.
+	fn distribute_seigniorage(
+		currency_id: CurrencyIdOf<T>,
+		amount: BalanceOf<T>,
+		shares: SeigniorageShares,
+	) -> result::Result<BalanceOf<T>, DispatchError> {
+		let payouts = [
+			(SeigniorageRecipient::Treasury, T::TreasuryAccount::get(), shares.treasury.mul_floor(amount)),
+			(SeigniorageRecipient::Serpers, T::SerperAccount::get(), shares.serpers.mul_floor(amount)),
+			(SeigniorageRecipient::Stakers, T::StakersAccount::get(), shares.stakers.mul_floor(amount)),
+		];
+		let mut paid = BalanceOf::<T>::zero();
+		let mut to_serpers = BalanceOf::<T>::zero();
+		for (recipient, who, payout) in payouts.iter() {
+			if !Self::is_seigniorage_dust(currency_id, who, *payout) {
+				Self::pay_seigniorage(currency_id, *recipient, who, *payout)?;
+				paid = paid.saturating_add(*payout);
+				if *recipient == SeigniorageRecipient::Serpers {
+					to_serpers = *payout;
+				}
+			}
+		}
+		let reserve_buffer = T::ReserveBufferAccount::get();
+		let to_reserve_buffer = amount.saturating_sub(paid);
+		if !Self::is_seigniorage_dust(currency_id, &reserve_buffer, to_reserve_buffer) {
+			Self::pay_seigniorage(
+				currency_id,
+				SeigniorageRecipient::ReserveBuffer,
+				&reserve_buffer,
+				to_reserve_buffer,
+			)?;
+			paid = paid.saturating_add(to_reserve_buffer);
+		}
+		Self::vest_expansion_proceeds(currency_id, to_serpers)?;
+		Ok(paid)
+	}
 
-		type Stp258Native: Stp258AssetExtended<Self::AccountId, Balance = BalanceOf<Self>, Amount = AmountOf<Self>>
-			+ Stp258AssetLockable<Self::AccountId, Balance = BalanceOf<Self>>
-			+ Stp258AssetReservable<Self::AccountId, Balance = BalanceOf<Self>>;
+	/// Whether issuing `amount` of `currency_id` to `who` would leave it
+	/// below the existential deposit.
+	fn is_seigniorage_dust(currency_id: CurrencyIdOf<T>, who: &T::AccountId, amount: BalanceOf<T>) -> bool {
+		Self::total_balance(currency_id, who).saturating_add(amount)
+			< <Self as Stp258Currency<T::AccountId>>::minimum_balance(currency_id)
+	}
 
-		#[pallet::constant]
-		type GetStp258NativeId: Get<CurrencyIdOf<Self>>;
+	/// Issue `amount` of `currency_id` to `who` as the seigniorage of
+	/// `recipient`.
+	fn pay_seigniorage(
+		currency_id: CurrencyIdOf<T>,
+		recipient: SeigniorageRecipient,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+		<Self as Stp258Currency<T::AccountId>>::deposit(currency_id, who, amount)?;
+		Self::deposit_event(Event::SeignioragePaid(currency_id, recipient, who.clone(), amount));
+		Ok(())
+	}
 
-		/// Weight information for extrinsics in this module.
-		type WeightInfo: WeightInfo;
+	/// The account holding the liquidity pools.
+	pub fn pool_account() -> T::AccountId {
+		T::PoolModuleId::get().into_account()
 	}
 
-	#[pallet::error]
-	pub enum Error<T> {
-		/// Unable to convert the Amount type into Balance.
-		AmountIntoBalanceFailed,
-		/// Balance is too low.
-		BalanceTooLow,
+	/// The price of a base unit of native currency in the pool of
+	/// `stable_currency_id`, or `None` if the pool is empty.
+	pub fn spot_price(stable_currency_id: CurrencyIdOf<T>) -> Option<BalanceOf<T>> {
+		let pool = Self::pools((T::GetStp258NativeId::get(), stable_currency_id));
+		if pool.native_reserve.is_zero() {
+			return None;
+		}
+		let native_unit = Self::base_unit(T::GetStp258NativeId::get()).max(One::one());
+		Self::mul_div(pool.stable_reserve, native_unit, pool.native_reserve).ok()
+	}
+
+	/// The pool of `stable_currency_id` with the native currency.
+	fn pool_id(
+		stable_currency_id: CurrencyIdOf<T>,
+	) -> result::Result<(CurrencyIdOf<T>, CurrencyIdOf<T>), DispatchError> {
+		ensure!(
+			StableCurrencies::<T>::contains_key(stable_currency_id),
+			Error::<T>::StableCurrencyNotRegistered
+		);
+		Ok((T::GetStp258NativeId::get(), stable_currency_id))
+	}
+
+	/// The pool reserves of `currency_in` and `currency_out`.
+	fn swap_reserves(
+		currency_in: CurrencyIdOf<T>,
+		currency_out: CurrencyIdOf<T>,
+	) -> result::Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+		let native_currency_id = T::GetStp258NativeId::get();
+		if currency_in == native_currency_id && currency_out != native_currency_id {
+			let pool = Self::pools(Self::pool_id(currency_out)?);
+			Ok((pool.native_reserve, pool.stable_reserve))
+		} else if currency_out == native_currency_id && currency_in != native_currency_id {
+			let pool = Self::pools(Self::pool_id(currency_in)?);
+			Ok((pool.stable_reserve, pool.native_reserve))
+		} else {
+			Err(Error::<T>::InvalidSwapPair.into())
+		}
 	}
 
-	#[pallet::event]
-	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
-	pub enum Event<T: Config> {
-		/// Currency transfer success. [currency_id, from, to, amount]
-		Transferred(CurrencyIdOf<T>, T::AccountId, T::AccountId, BalanceOf<T>),
-		/// Update balance success. [currency_id, who, amount]
-		BalanceUpdated(CurrencyIdOf<T>, T::AccountId, AmountOf<T>),
-		/// Deposit success. [currency_id, who, amount]
-		Deposited(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
-		/// Withdraw success. [currency_id, who, amount]
-		Withdrawn(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
-		// Supply Expansion Successful. \[currency_id, expand_by\]
-		SerpedUpSupply(CurrencyIdOf<T>, BalanceOf<T>),
-		/// Supply Contraction Successful. \[currency_id, contract_by\]
-		SerpedDownSupply(CurrencyIdOf<T>, BalanceOf<T>),
+	/// The amount out of a pool for `amount_in`, after the swap fee.
+	fn amount_out(
+		amount_in: BalanceOf<T>,
+		reserve_in: BalanceOf<T>,
+		reserve_out: BalanceOf<T>,
+	) -> result::Result<BalanceOf<T>, DispatchError> {
+		let amount_in = amount_in.saturating_sub(T::SwapFee::get().mul_ceil(amount_in));
+		let amount_out = Self::mul_div(amount_in, reserve_out, reserve_in.saturating_add(amount_in))?;
+		ensure!(!amount_out.is_zero(), Error::<T>::InsufficientLiquidity);
+		Ok(amount_out)
+	}
+
+	/// The amount into a pool for `amount_out`, after the swap fee.
+	fn amount_in(
+		amount_out: BalanceOf<T>,
+		reserve_in: BalanceOf<T>,
+		reserve_out: BalanceOf<T>,
+	) -> result::Result<BalanceOf<T>, DispatchError> {
+		ensure!(
+			!amount_out.is_zero() && amount_out < reserve_out,
+			Error::<T>::InsufficientLiquidity
+		);
+		let amount_in = Self::mul_div(amount_out, reserve_in, reserve_out.saturating_sub(amount_out))?
+			.saturating_add(One::one());
+		let whole: BalanceOf<T> = Perbill::from_percent(100).deconstruct().into();
+		let fee: BalanceOf<T> = T::SwapFee::get().deconstruct().into();
+		Ok(Self::mul_div(amount_in, whole, whole.saturating_sub(fee))?.saturating_add(One::one()))
+	}
+
+	/// Move `amount_in` of `currency_in` from `who` into its pool, and
+	/// `amount_out` of `currency_out` out of it to `who`.
+	fn swap(
+		who: &T::AccountId,
+		currency_in: CurrencyIdOf<T>,
+		currency_out: CurrencyIdOf<T>,
+		amount_in: BalanceOf<T>,
+		amount_out: BalanceOf<T>,
+	) -> DispatchResult {
+		let native_in = currency_in == T::GetStp258NativeId::get();
+		let pool_id = if native_in {
+			(currency_in, currency_out)
+		} else {
+			(currency_out, currency_in)
+		};
+		let pool_account = Self::pool_account();
+		with_transaction_result(|| {
+			<Self as Stp258Currency<T::AccountId>>::transfer(currency_in, who, &pool_account, amount_in)?;
+			<Self as Stp258Currency<T::AccountId>>::transfer(currency_out, &pool_account, who, amount_out)
+		})?;
+		Pools::<T>::mutate(pool_id, |pool| {
+			if native_in {
+				pool.native_reserve = pool.native_reserve.saturating_add(amount_in);
+				pool.stable_reserve = pool.stable_reserve.saturating_sub(amount_out);
+			} else {
+				pool.stable_reserve = pool.stable_reserve.saturating_add(amount_in);
+				pool.native_reserve = pool.native_reserve.saturating_sub(amount_out);
+			}
+		});
+		Self::deposit_event(Event::Swapped(who.clone(), currency_in, currency_out, amount_in, amount_out));
+		Ok(())
 	}
 
-	#[pallet::pallet]
-	pub struct Pallet<T>(PhantomData<T>);
+	/// `a * b / c`, failing when `c` is zero.
+	fn mul_div(a: BalanceOf<T>, b: BalanceOf<T>, c: BalanceOf<T>) -> result::Result<BalanceOf<T>, DispatchError> {
+		multiply_by_rational(a.unique_saturated_into(), b.unique_saturated_into(), c.unique_saturated_into())
+			.map(BalanceOf::<T>::unique_saturated_from)
+			.map_err(|_| Error::<T>::InsufficientLiquidity.into())
+	}
+
+	/// The stable currency cost of `amount` of native currency at `price`
+	/// per base unit of native currency.
+	fn order_cost(amount: BalanceOf<T>, price: BalanceOf<T>) -> result::Result<BalanceOf<T>, DispatchError> {
+		let native_unit = Self::base_unit(T::GetStp258NativeId::get()).max(One::one());
+		Self::mul_div(amount, price, native_unit).map_err(|_| Error::<T>::InvalidOrder.into())
+	}
+
+	/// Remove the order `order_id` of `currency_id` from storage and the open
+	/// orders of its owner, unreserving its remaining funds. The caller
+	/// removes it from the order book.
+	fn close_order(currency_id: CurrencyIdOf<T>, order_id: OrderId, order: &Order<T::AccountId, BalanceOf<T>>) {
+		let reserve_currency_id = match order.side {
+			OrderSide::Buy => currency_id,
+			OrderSide::Sell => T::GetStp258NativeId::get(),
+		};
+		<Self as Stp258CurrencyReservable<T::AccountId>>::unreserve(reserve_currency_id, &order.owner, order.reserved);
+		Orders::<T>::remove(currency_id, order_id);
+		OpenOrders::<T>::mutate_exists(&order.owner, |maybe_open_orders| {
+			if let Some(open_orders) = maybe_open_orders {
+				open_orders.retain(|&open_order| open_order != (currency_id, order_id));
+				if open_orders.is_empty() {
+					*maybe_open_orders = None;
+				}
+			}
+		});
+	}
 
-	#[pallet::hooks]
-	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+	/// Store the buy orders of `currency_id`, removing the book once empty and
+	/// keeping `BuyOrderBookCount` in step.
+	fn put_buy_orders(currency_id: CurrencyIdOf<T>, book: Vec<(BalanceOf<T>, OrderId)>) {
+		let existed = BuyOrders::<T>::contains_key(currency_id);
+		if book.is_empty() {
+			BuyOrders::<T>::remove(currency_id);
+			if existed {
+				BuyOrderBookCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			}
+		} else {
+			BuyOrders::<T>::insert(currency_id, book);
+			if !existed {
+				BuyOrderBookCount::<T>::mutate(|count| *count = count.saturating_add(1));
+			}
+		}
+	}
 
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
-		/// Transfer some balance to another account under `currency_id`.
-		///
-		/// The dispatch origin for this call must be `Signed` by the
-		/// transactor.
-		#[pallet::weight(T::WeightInfo::transfer_non_native_currency())]
-		pub fn transfer(
-			origin: OriginFor<T>,
-			dest: <T::Lookup as StaticLookup>::Source,
-			currency_id: CurrencyIdOf<T>,
-			#[pallet::compact] amount: BalanceOf<T>,
-		) -> DispatchResultWithPostInfo {
-			let from = ensure_signed(origin)?;
-			let to = T::Lookup::lookup(dest)?;
-			<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &from, &to, amount)?;
-			Ok(().into())
+	/// Match the crossing buy and sell orders of `currency_id`, up to
+	/// `MaxOrderMatches` times. An order whose reserved funds no longer cover
+	/// its fill, or which is too small to cost anything at the fill price, is
+	/// cancelled.
+	fn match_orders(currency_id: CurrencyIdOf<T>) {
+		let mut buys = Self::buy_orders(currency_id);
+		let mut sells = Self::sell_orders(currency_id);
+		let mut matched = 0u32;
+
+		while matched < T::MaxOrderMatches::get() {
+			let (buy_price, buy_id, sell_price, sell_id) = match (buys.first(), sells.first()) {
+				(Some(&(buy_price, buy_id)), Some(&(sell_price, sell_id))) if buy_price >= sell_price => {
+					(buy_price, buy_id, sell_price, sell_id)
+				}
+				_ => break,
+			};
+			matched += 1;
+
+			let (mut buy, mut sell) = match (Self::orders(currency_id, buy_id), Self::orders(currency_id, sell_id)) {
+				(Some(buy), Some(sell)) => (buy, sell),
+				(None, _) => {
+					buys.remove(0);
+					continue;
+				}
+				(_, None) => {
+					sells.remove(0);
+					continue;
+				}
+			};
+			let price = if buy_id < sell_id { buy_price } else { sell_price };
+			let amount = buy.amount.min(sell.amount);
+
+			if let Err(short_side) = Self::fill_orders(currency_id, &mut buy, &mut sell, amount, price) {
+				let (short_id, short) = match short_side {
+					OrderSide::Buy => {
+						buys.remove(0);
+						(buy_id, buy)
+					}
+					OrderSide::Sell => {
+						sells.remove(0);
+						(sell_id, sell)
+					}
+				};
+				Self::close_order(currency_id, short_id, &short);
+				Self::deposit_event(Event::OrderCancelled(currency_id, short_id, short.owner));
+				continue;
+			}
+			Self::deposit_event(Event::OrdersMatched(currency_id, buy_id, sell_id, amount, price));
+
+			if buy.amount.is_zero() {
+				buys.remove(0);
+				Self::close_order(currency_id, buy_id, &buy);
+			} else {
+				Orders::<T>::insert(currency_id, buy_id, buy);
+			}
+			if sell.amount.is_zero() {
+				sells.remove(0);
+				Self::close_order(currency_id, sell_id, &sell);
+			} else {
+				Orders::<T>::insert(currency_id, sell_id, sell);
+			}
 		}
 
-		/// Transfer some native currency to another account.
-		///
-		/// The dispatch origin for this call must be `Signed` by the
-		/// transactor.
-		#[pallet::weight(T::WeightInfo::transfer_native_currency())]
-		pub fn transfer_native_currency(
-			origin: OriginFor<T>,
-			dest: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: BalanceOf<T>,
-		) -> DispatchResultWithPostInfo {
-			let from = ensure_signed(origin)?;
-			let to = T::Lookup::lookup(dest)?;
-			T::Stp258Native::transfer(&from, &to, amount)?;
+		Self::put_buy_orders(currency_id, buys);
+		if sells.is_empty() {
+			SellOrders::<T>::remove(currency_id);
+		} else {
+			SellOrders::<T>::insert(currency_id, sells);
+		}
+	}
 
-			Self::deposit_event(Event::Transferred(T::GetStp258NativeId::get(), from, to, amount));
-			Ok(().into())
+	/// Fill `amount` of native currency of `buy` and `sell` at `price`,
+	/// repatriating the reserved funds of each side to the other and
+	/// unreserving what the buyer reserved beyond `price`. Fails with the
+	/// side whose reserved funds came up short, or with the side it would
+	/// fill completely if the fill costs nothing, leaving both untouched.
+	fn fill_orders(
+		currency_id: CurrencyIdOf<T>,
+		buy: &mut Order<T::AccountId, BalanceOf<T>>,
+		sell: &mut Order<T::AccountId, BalanceOf<T>>,
+		amount: BalanceOf<T>,
+		price: BalanceOf<T>,
+	) -> result::Result<(), OrderSide> {
+		let native_currency_id = T::GetStp258NativeId::get();
+		let cost = Self::order_cost(amount, price).map_err(|_| OrderSide::Buy)?;
+		if cost.is_zero() {
+			return Err(if amount == buy.amount { OrderSide::Buy } else { OrderSide::Sell });
+		}
+		let released = if amount == buy.amount {
+			buy.reserved
+		} else {
+			Self::order_cost(amount, buy.price).map_err(|_| OrderSide::Buy)?.min(buy.reserved)
+		};
+		if cost > released {
+			return Err(OrderSide::Buy);
 		}
 
-		/// update amount of account `who` under `currency_id`.
-		///
-		/// The dispatch origin of this call must be _Root_.
-		#[pallet::weight(T::WeightInfo::update_balance_non_native_currency())]
-		pub fn update_balance(
-			origin: OriginFor<T>,
-			who: <T::Lookup as StaticLookup>::Source,
-			currency_id: CurrencyIdOf<T>,
-			amount: AmountOf<T>,
-		) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
-			let dest = T::Lookup::lookup(who)?;
-			<Self as Stp258CurrencyExtended<T::AccountId>>::update_balance(currency_id, &dest, amount)?;
-			Ok(().into())
+		let mut short_side = OrderSide::Sell;
+		with_transaction_result(|| {
+			let native_missing = <Self as Stp258CurrencyReservable<T::AccountId>>::repatriate_reserved(
+				native_currency_id,
+				&sell.owner,
+				&buy.owner,
+				amount,
+				BalanceStatus::Free,
+			)?;
+			ensure!(native_missing.is_zero(), Error::<T>::OrderFundsMissing);
+			short_side = OrderSide::Buy;
+			let stable_missing = <Self as Stp258CurrencyReservable<T::AccountId>>::repatriate_reserved(
+				currency_id,
+				&buy.owner,
+				&sell.owner,
+				cost,
+				BalanceStatus::Free,
+			)?;
+			ensure!(stable_missing.is_zero(), Error::<T>::OrderFundsMissing);
+			Ok(())
+		})
+		.map_err(|_| short_side)?;
+		<Self as Stp258CurrencyReservable<T::AccountId>>::unreserve(
+			currency_id,
+			&buy.owner,
+			released.saturating_sub(cost),
+		);
+
+		buy.amount = buy.amount.saturating_sub(amount);
+		buy.reserved = buy.reserved.saturating_sub(released);
+		sell.amount = sell.amount.saturating_sub(amount);
+		sell.reserved = sell.reserved.saturating_sub(amount);
+		Ok(())
+	}
+
+	/// Ensure neither the market nor `currency_id` is paused.
+	fn ensure_not_paused(currency_id: CurrencyIdOf<T>) -> DispatchResult {
+		ensure!(!Self::market_paused(), Error::<T>::MarketPaused);
+		ensure!(!Self::paused_currencies(currency_id), Error::<T>::CurrencyPaused);
+		Ok(())
+	}
+
+	/// Ensure `native_currency_id` is the native currency and
+	/// `stable_currency_id` is an enabled stable currency the market may serp,
+	/// returning its metadata.
+	fn ensure_serpable(
+		native_currency_id: CurrencyIdOf<T>,
+		stable_currency_id: CurrencyIdOf<T>,
+	) -> result::Result<StableCurrencyInfo<BalanceOf<T>>, DispatchError> {
+		ensure!(
+			native_currency_id == T::GetStp258NativeId::get(),
+			Error::<T>::UnrecognisedNativeCurrency
+		);
+		ensure!(
+			stable_currency_id != T::GetStp258NativeId::get(),
+			Error::<T>::NativeCurrencyCannotBeSerped
+		);
+		Self::ensure_not_paused(stable_currency_id)?;
+		let info = Self::stable_currencies(stable_currency_id).ok_or(Error::<T>::StableCurrencyNotRegistered)?;
+		ensure!(info.enabled, Error::<T>::StableCurrencyDisabled);
+		Ok(info)
+	}
+
+	/// The tally of `currency_id` including `adjustment`, along with its
+	/// block and window totals in the direction of `adjustment`.
+	fn tally_supply_adjustment(
+		currency_id: CurrencyIdOf<T>,
+		adjustment: SupplyAdjustment<BalanceOf<T>>,
+	) -> (SupplyAdjustmentTally<T::BlockNumber, BalanceOf<T>>, BalanceOf<T>, BalanceOf<T>) {
+		let now = frame_system::Module::<T>::block_number();
+		let window = T::CapWindow::get();
+		let mut tally = Self::supply_adjustment_tallies(currency_id);
+		tally.buckets.retain(|bucket| bucket.block.saturating_add(window) > now);
+		let mut bucket = match tally.buckets.pop() {
+			Some(bucket) if bucket.block == now => bucket,
+			last => {
+				tally.buckets.extend(last);
+				SupplyAdjustmentBucket {
+					block: now,
+					..Default::default()
+				}
+			}
+		};
+		let block_total = match adjustment {
+			SupplyAdjustment::Expand(amount) => {
+				bucket.expanded = bucket.expanded.saturating_add(amount);
+				bucket.expanded
+			}
+			SupplyAdjustment::Contract(amount) => {
+				bucket.contracted = bucket.contracted.saturating_add(amount);
+				bucket.contracted
+			}
+		};
+		tally.buckets.push(bucket);
+		let window_total = tally.buckets.iter().fold(BalanceOf::<T>::zero(), |total, bucket| match adjustment {
+			SupplyAdjustment::Expand(_) => total.saturating_add(bucket.expanded),
+			SupplyAdjustment::Contract(_) => total.saturating_add(bucket.contracted),
+		});
+		(tally, block_total, window_total)
+	}
+
+	/// Ensure `adjustment` keeps the supply adjustments of `currency_id`
+	/// within its supply caps, returning the tally including it.
+	///
+	/// A breach is reported with a `SupplyCapBreached` event.
+	fn ensure_within_supply_caps(
+		currency_id: CurrencyIdOf<T>,
+		adjustment: SupplyAdjustment<BalanceOf<T>>,
+	) -> result::Result<SupplyAdjustmentTally<T::BlockNumber, BalanceOf<T>>, DispatchError> {
+		let (tally, block_total, window_total) = Self::tally_supply_adjustment(currency_id, adjustment);
+		let amount = match adjustment {
+			SupplyAdjustment::Expand(amount) | SupplyAdjustment::Contract(amount) => amount,
+		};
+		if let Some(caps) = Self::supply_caps(currency_id) {
+			let total_issuance = <Self as Stp258Currency<T::AccountId>>::total_issuance(currency_id);
+			let breach = if amount > caps.per_call.limit(total_issuance) {
+				Some(Error::<T>::ExceedsCallCap)
+			} else if block_total > caps.per_block.limit(total_issuance) {
+				Some(Error::<T>::ExceedsBlockCap)
+			} else if window_total > caps.per_window.limit(total_issuance) {
+				Some(Error::<T>::ExceedsWindowCap)
+			} else {
+				None
+			};
+			if let Some(error) = breach {
+				Self::deposit_event(Event::SupplyCapBreached(currency_id, adjustment));
+				return Err(error.into());
+			}
+		}
+		Ok(tally)
+	}
+
+	/// The amount `currency_id` can still be adjusted by in the direction of
+	/// `adjustment` without breaching its supply caps, or `None` if uncapped.
+	fn supply_cap_room(
+		currency_id: CurrencyIdOf<T>,
+		adjustment: SupplyAdjustment<BalanceOf<T>>,
+	) -> Option<BalanceOf<T>> {
+		let caps = Self::supply_caps(currency_id)?;
+		let unchanged = match adjustment {
+			SupplyAdjustment::Expand(_) => SupplyAdjustment::Expand(Zero::zero()),
+			SupplyAdjustment::Contract(_) => SupplyAdjustment::Contract(Zero::zero()),
+		};
+		let (_, block_total, window_total) = Self::tally_supply_adjustment(currency_id, unchanged);
+		let total_issuance = <Self as Stp258Currency<T::AccountId>>::total_issuance(currency_id);
+		Some(
+			caps.per_call
+				.limit(total_issuance)
+				.min(caps.per_block.limit(total_issuance).saturating_sub(block_total))
+				.min(caps.per_window.limit(total_issuance).saturating_sub(window_total)),
+		)
+	}
+
+	/// Expand or contract the supply of `currency_id` by the adjustment
+	/// `T::SupplyAdjuster` computes from its market price and peg, clamped to
+	/// its `max_expansion` and to the room left under its supply caps. The
+	/// adjuster only commits its state once the adjustment succeeds.
+	fn serp_to_peg(currency_id: CurrencyIdOf<T>, info: &StableCurrencyInfo<BalanceOf<T>>) -> DispatchResult {
+		let market_price = T::PriceProvider::get_price(currency_id).ok_or(Error::<T>::PriceUnavailable)?;
+		if info.peg.is_zero() {
+			return Ok(());
 		}
+
+		let native_currency_id = T::GetStp258NativeId::get();
+		let total_issuance = <Self as Stp258Currency<T::AccountId>>::total_issuance(currency_id);
+		let adjustment = match T::SupplyAdjuster::adjustment(currency_id, total_issuance, info.peg, market_price) {
+			Some(adjustment) => adjustment,
+			None => return Ok(()),
+		};
+		let room = Self::supply_cap_room(currency_id, adjustment);
+		let clamp = |amount: BalanceOf<T>| room.map_or(amount, |room| amount.min(room));
+		let adjusted = match adjustment {
+			SupplyAdjustment::Expand(expand_by) => {
+				let expand_by = clamp(expand_by.min(info.max_expansion));
+				if expand_by.is_zero() {
+					return Ok(());
+				}
+				<Self as SerpMarket<T::AccountId>>::expand_supply(
+					native_currency_id,
+					currency_id,
+					expand_by,
+					market_price,
+				)
+			}
+			SupplyAdjustment::Contract(contract_by) => {
+				let contract_by = clamp(contract_by);
+				if contract_by.is_zero() {
+					return Ok(());
+				}
+				<Self as SerpMarket<T::AccountId>>::contract_supply(
+					native_currency_id,
+					currency_id,
+					contract_by,
+					market_price,
+				)
+			}
+		};
+		adjusted?;
+		T::SupplyAdjuster::on_adjusted(currency_id, info.peg, market_price);
+		Ok(())
 	}
 }
 
@@ -179,21 +3016,36 @@ impl<T: Config> SerpMarket<T::AccountId> for Pallet<T> {
 		if expand_by.is_zero() {
 			return Ok(());
 		}
-		if native_currency_id == T::GetStp258NativeId::get() {
-			if stable_currency_id != T::GetStp258NativeId::get() {
-				T::Stp258Currency::expand_supply(
-					native_currency_id, 
-					stable_currency_id, 
-					expand_by as Self::Balance, 
-					quote_price,
-				)?;
-			} else {
-				native::info!("💸 This currency cannot be serped.");
+		let info = Self::ensure_serpable(native_currency_id, stable_currency_id)?;
+		ensure!(expand_by <= info.max_expansion, Error::<T>::ExceedsMaxExpansion);
+		Self::ensure_within_supply_caps(stable_currency_id, SupplyAdjustment::Expand(expand_by))?;
+		let expanded = with_transaction_result(|| {
+			BondOffers::<T>::remove(stable_currency_id);
+			BondOfferQuotes::<T>::remove(stable_currency_id);
+			let redeemed = Self::redeem_bonds(stable_currency_id, expand_by);
+			let to_issue = expand_by.saturating_sub(redeemed);
+			if to_issue.is_zero() {
+				return Ok(redeemed);
 			}
-		} else {
-			native::info!("💸 The native serping currency is not recognised.");
+			if let Some(shares) = Self::seigniorage_shares() {
+				let issued = Self::distribute_seigniorage(stable_currency_id, to_issue, shares)?;
+				return Ok(redeemed.saturating_add(issued));
+			}
+			let serper_balance = Self::total_balance(stable_currency_id, &T::SerperAccount::get());
+			T::Stp258Currency::expand_supply(native_currency_id, stable_currency_id, to_issue, quote_price)?;
+			let proceeds =
+				Self::total_balance(stable_currency_id, &T::SerperAccount::get()).saturating_sub(serper_balance);
+			Self::vest_expansion_proceeds(stable_currency_id, proceeds)?;
+			Ok(expand_by)
+		})?;
+		if expanded.is_zero() {
+			return Ok(());
 		}
-		Self::deposit_event(Event::SerpedUpSupply(stable_currency_id, expand_by));
+		// Seigniorage dust is left unissued, so only what was issued is counted.
+		let (tally, ..) = Self::tally_supply_adjustment(stable_currency_id, SupplyAdjustment::Expand(expanded));
+		SupplyAdjustmentTallies::<T>::insert(stable_currency_id, tally);
+		Self::record_supply_adjustment(stable_currency_id, SupplyAdjustment::Expand(expanded), quote_price);
+		Self::deposit_event(Event::SerpedUpSupply(stable_currency_id, expanded));
 		Ok(())
 	}
 
@@ -214,20 +3066,24 @@ impl<T: Config> SerpMarket<T::AccountId> for Pallet<T> {
 		if contract_by.is_zero() {
 			return Ok(());
 		}
-		if native_currency_id == T::GetStp258NativeId::get() {
-			if stable_currency_id != T::GetStp258NativeId::get() {
-				T::Stp258Currency::contract_supply(
-					native_currency_id, 
-					stable_currency_id, 
-					contract_by,
-					quote_price,
-				)?;
-			} else {
-				native::info!("💸 This currency cannot be serped.");
-			}
-		} else {
-			native::info!("💸 The native serping currency is not recognised.");
+		Self::ensure_serpable(native_currency_id, stable_currency_id)?;
+		let tally = Self::ensure_within_supply_caps(stable_currency_id, SupplyAdjustment::Contract(contract_by))?;
+		if !T::AuctionDuration::get().is_zero() {
+			// The tally is charged as the auction burns, in `settle_auction`.
+			return Self::start_auction(stable_currency_id, contract_by, quote_price);
+		}
+		let reserve = T::Stp258Currency::reserved_balance(stable_currency_id, &T::SerperAccount::get());
+		if reserve < contract_by && !T::BondDuration::get().is_zero() {
+			BondOffers::<T>::mutate(stable_currency_id, |offered| *offered = offered.saturating_add(contract_by));
+			BondOfferQuotes::<T>::insert(stable_currency_id, quote_price);
+			Self::deposit_event(Event::BondsOffered(stable_currency_id, contract_by));
+			return Ok(());
 		}
+		with_transaction_result(|| {
+			T::Stp258Currency::contract_supply(native_currency_id, stable_currency_id, contract_by, quote_price)
+		})?;
+		SupplyAdjustmentTallies::<T>::insert(stable_currency_id, tally);
+		Self::record_supply_adjustment(stable_currency_id, SupplyAdjustment::Contract(contract_by), quote_price);
 		Self::deposit_event(Event::SerpedDownSupply(stable_currency_id, contract_by));
 		Ok(())
 	}
@@ -240,6 +3096,8 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 	fn base_unit(currency_id: Self::CurrencyId) -> Self::Balance {
 		if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::minimum_balance()
+		} else if let Some(info) = Self::stable_currencies(currency_id) {
+			info.base_unit
 		} else {
 			T::Stp258Currency::base_unit(currency_id)
 		}
@@ -278,11 +3136,7 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 	}
 
 	fn ensure_can_withdraw(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
-		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::ensure_can_withdraw(who, amount)
-		} else {
-			T::Stp258Currency::ensure_can_withdraw(currency_id, who, amount)
-		}
+		Self::ensure_can_withdraw_with_reasons(currency_id, who, amount, WithdrawReasons::all())
 	}
 
 	fn transfer(
@@ -294,6 +3148,7 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 		if amount.is_zero() || from == to {
 			return Ok(());
 		}
+		Self::ensure_not_paused(currency_id)?;
 		if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::transfer(from, to, amount)?;
 		} else {
@@ -317,16 +3172,7 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 	}
 
 	fn withdraw(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
-		if amount.is_zero() {
-			return Ok(());
-		}
-		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::withdraw(who, amount)?;
-		} else {
-			T::Stp258Currency::withdraw(currency_id, who, amount)?;
-		}
-		Self::deposit_event(Event::Withdrawn(currency_id, who.clone(), amount));
-		Ok(())
+		Self::withdraw_with_reasons(currency_id, who, amount, WithdrawReasons::all())
 	}
 
 	fn can_slash(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> bool {
@@ -338,63 +3184,174 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 	}
 
 	fn slash(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> Self::Balance {
+		let gap = if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::slash(who, amount)
+		} else {
+			T::Stp258Currency::slash(currency_id, who, amount)
+		};
+		let slashed = amount.saturating_sub(gap);
+		if !slashed.is_zero() {
+			Self::deposit_event(Event::Slashed(currency_id, who.clone(), slashed));
+		}
+		gap
+	}
+}
+
+impl<T: Config> Stp258CurrencyExtended<T::AccountId> for Pallet<T> {
+	type Amount = AmountOf<T>;
+
+	fn update_balance(currency_id: Self::CurrencyId, who: &T::AccountId, by_amount: Self::Amount) -> DispatchResult {
+		Self::ensure_not_paused(currency_id)?;
+		if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::update_balance(who, by_amount)?;
+		} else {
+			T::Stp258Currency::update_balance(currency_id, who, by_amount)?;
+		}
+		Self::deposit_event(Event::BalanceUpdated(currency_id, who.clone(), by_amount));
+		Ok(())
+	}
+}
+
+impl<T: Config> Stp258CurrencyLockable<T::AccountId> for Pallet<T> {
+	type Moment = T::BlockNumber;
+
+	fn set_lock(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		Self::set_lock_with_reasons(lock_id, currency_id, who, amount, WithdrawReasons::all())
+	}
+
+	fn extend_lock(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		Self::extend_lock_with_reasons(lock_id, currency_id, who, amount, WithdrawReasons::all())
+	}
+
+	fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) -> DispatchResult {
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::slash(who, amount)
+			T::Stp258Native::remove_lock(lock_id, who)?;
 		} else {
-			T::Stp258Currency::slash(currency_id, who, amount)
+			T::Stp258Currency::remove_lock(lock_id, currency_id, who)?;
 		}
+		LockExpiries::<T>::remove(who, (currency_id, lock_id));
+		LockAmounts::<T>::remove(who, (currency_id, lock_id));
+		Self::deposit_event(Event::LockRemoved(lock_id, currency_id, who.clone()));
+		Ok(())
 	}
 }
 
-impl<T: Config> Stp258CurrencyExtended<T::AccountId> for Pallet<T> {
-	type Amount = AmountOf<T>;
+impl<T: Config> Stp258CurrencyTimedLockable<T::AccountId> for Pallet<T> {
+	fn set_lock_until(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		until: Self::Moment,
+	) -> DispatchResult {
+		ensure!(
+			until > frame_system::Module::<T>::block_number(),
+			Error::<T>::LockExpiryInPast
+		);
+		let entry = (lock_id, currency_id, who.clone());
+		let mut expiring = ExpiringLocks::<T>::get(until);
+		let is_queued = expiring.contains(&entry);
+		ensure!(
+			is_queued || (expiring.len() as u32) < T::MaxLockExpiries::get(),
+			Error::<T>::TooManyLockExpiries
+		);
+
+		<Self as Stp258CurrencyLockable<T::AccountId>>::set_lock(lock_id, currency_id, who, amount)?;
+		if !is_queued {
+			expiring.push(entry);
+			ExpiringLocks::<T>::insert(until, expiring);
+		}
+		LockExpiries::<T>::insert(who, (currency_id, lock_id), until);
+		Ok(())
+	}
 
-	fn update_balance(currency_id: Self::CurrencyId, who: &T::AccountId, by_amount: Self::Amount) -> DispatchResult {
+	fn lock_expiry(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) -> Option<Self::Moment> {
+		LockExpiries::<T>::get(who, (currency_id, lock_id))
+	}
+}
+
+impl<T: Config> Stp258CurrencyLockableWithReasons<T::AccountId> for Pallet<T> {
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::update_balance(who, by_amount)?;
+			T::Stp258Native::set_lock_with_reasons(lock_id, who, amount, reasons)?;
 		} else {
-			T::Stp258Currency::update_balance(currency_id, who, by_amount)?;
+			ensure!(reasons == WithdrawReasons::all(), Error::<T>::WithdrawReasonsNotSupported);
+			T::Stp258Currency::set_lock(lock_id, currency_id, who, amount)?;
 		}
-		Self::deposit_event(Event::BalanceUpdated(currency_id, who.clone(), by_amount));
+		LockExpiries::<T>::remove(who, (currency_id, lock_id));
+		LockAmounts::<T>::insert(who, (currency_id, lock_id), amount);
+		Self::deposit_event(Event::LockSet(lock_id, currency_id, who.clone(), amount));
 		Ok(())
 	}
-}
-
-impl<T: Config> Stp258CurrencyLockable<T::AccountId> for Pallet<T> {
-	type Moment = T::BlockNumber;
 
-	fn set_lock(
+	fn extend_lock_with_reasons(
 		lock_id: LockIdentifier,
 		currency_id: Self::CurrencyId,
 		who: &T::AccountId,
 		amount: Self::Balance,
+		reasons: WithdrawReasons,
 	) -> DispatchResult {
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::set_lock(lock_id, who, amount)
+			T::Stp258Native::extend_lock_with_reasons(lock_id, who, amount, reasons)?;
 		} else {
-			T::Stp258Currency::set_lock(lock_id, currency_id, who, amount)
+			ensure!(reasons == WithdrawReasons::all(), Error::<T>::WithdrawReasonsNotSupported);
+			T::Stp258Currency::extend_lock(lock_id, currency_id, who, amount)?;
 		}
+		let amount = LockAmounts::<T>::mutate(who, (currency_id, lock_id), |locked| {
+			*locked = (*locked).max(amount);
+			*locked
+		});
+		Self::deposit_event(Event::LockExtended(lock_id, currency_id, who.clone(), amount));
+		Ok(())
 	}
 
-	fn extend_lock(
-		lock_id: LockIdentifier,
+	fn ensure_can_withdraw_with_reasons(
 		currency_id: Self::CurrencyId,
 		who: &T::AccountId,
 		amount: Self::Balance,
+		reasons: WithdrawReasons,
 	) -> DispatchResult {
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::extend_lock(lock_id, who, amount)
+			T::Stp258Native::ensure_can_withdraw_with_reasons(who, amount, reasons)
 		} else {
-			T::Stp258Currency::extend_lock(lock_id, currency_id, who, amount)
+			ensure!(reasons == WithdrawReasons::all(), Error::<T>::WithdrawReasonsNotSupported);
+			T::Stp258Currency::ensure_can_withdraw(currency_id, who, amount)
 		}
 	}
 
-	fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) -> DispatchResult {
+	fn withdraw_with_reasons(
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::remove_lock(lock_id, who)
+			T::Stp258Native::withdraw_with_reasons(who, amount, reasons)?;
 		} else {
-			T::Stp258Currency::remove_lock(lock_id, currency_id, who)
+			ensure!(reasons == WithdrawReasons::all(), Error::<T>::WithdrawReasonsNotSupported);
+			T::Stp258Currency::withdraw(currency_id, who, amount)?;
 		}
+		Self::deposit_event(Event::Withdrawn(currency_id, who.clone(), amount));
+		Ok(())
 	}
 }
 
@@ -408,11 +3365,9 @@ impl<T: Config> Stp258CurrencyReservable<T::AccountId> for Pallet<T> {
 	}
 
 	fn slash_reserved(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
-		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::slash_reserved(who, value)
-		} else {
-			T::Stp258Currency::slash_reserved(currency_id, who, value)
-		}
+		let to_slash = value.min(Self::unnamed_reserved_balance(currency_id, who));
+		let gap = Self::slash_any_reserved(currency_id, who, to_slash);
+		value.saturating_sub(to_slash).saturating_add(gap)
 	}
 
 	fn reserved_balance(currency_id: Self::CurrencyId, who: &T::AccountId) -> Self::Balance {
@@ -424,19 +3379,22 @@ impl<T: Config> Stp258CurrencyReservable<T::AccountId> for Pallet<T> {
 	}
 
 	fn reserve(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		if value.is_zero() {
+			return Ok(());
+		}
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::reserve(who, value)
+			T::Stp258Native::reserve(who, value)?;
 		} else {
-			T::Stp258Currency::reserve(currency_id, who, value)
+			T::Stp258Currency::reserve(currency_id, who, value)?;
 		}
+		Self::deposit_event(Event::Reserved(currency_id, who.clone(), value));
+		Ok(())
 	}
 
 	fn unreserve(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
-		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::unreserve(who, value)
-		} else {
-			T::Stp258Currency::unreserve(currency_id, who, value)
-		}
+		let to_unreserve = value.min(Self::unnamed_reserved_balance(currency_id, who));
+		let gap = Self::unreserve_any(currency_id, who, to_unreserve);
+		value.saturating_sub(to_unreserve).saturating_add(gap)
 	}
 
 	fn repatriate_reserved(
@@ -446,11 +3404,74 @@ impl<T: Config> Stp258CurrencyReservable<T::AccountId> for Pallet<T> {
 		value: Self::Balance,
 		status: BalanceStatus,
 	) -> result::Result<Self::Balance, DispatchError> {
-		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::repatriate_reserved(slashed, beneficiary, value, status)
-		} else {
-			T::Stp258Currency::repatriate_reserved(currency_id, slashed, beneficiary, value, status)
+		let to_repatriate = value.min(Self::unnamed_reserved_balance(currency_id, slashed));
+		let gap = Self::repatriate_any_reserved(currency_id, slashed, beneficiary, to_repatriate, status)?;
+		Ok(value.saturating_sub(to_repatriate).saturating_add(gap))
+	}
+}
+
+impl<T: Config> Stp258CurrencyNamedReservable<T::AccountId> for Pallet<T> {
+	fn reserved_balance_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+	) -> Self::Balance {
+		NamedReserves::<T>::get(who, (currency_id, *id)).unwrap_or_default()
+	}
+
+	fn reserve_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> DispatchResult {
+		<Self as Stp258CurrencyReservable<T::AccountId>>::reserve(currency_id, who, value)?;
+		Self::mutate_named_reserve(id, currency_id, who, value, true);
+		Ok(())
+	}
+
+	fn unreserve_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Self::Balance {
+		let to_unreserve = value.min(Self::reserved_balance_named(id, currency_id, who));
+		let gap = Self::unreserve_any(currency_id, who, to_unreserve);
+		let unreserved = to_unreserve.saturating_sub(gap);
+		Self::mutate_named_reserve(id, currency_id, who, unreserved, false);
+		value.saturating_sub(unreserved)
+	}
+
+	fn slash_reserved_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Self::Balance {
+		let to_slash = value.min(Self::reserved_balance_named(id, currency_id, who));
+		let gap = Self::slash_any_reserved(currency_id, who, to_slash);
+		let slashed = to_slash.saturating_sub(gap);
+		Self::mutate_named_reserve(id, currency_id, who, slashed, false);
+		value.saturating_sub(slashed)
+	}
+
+	fn repatriate_reserved_named(
+		id: &ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError> {
+		let to_repatriate = value.min(Self::reserved_balance_named(id, currency_id, slashed));
+		let gap = Self::repatriate_any_reserved(currency_id, slashed, beneficiary, to_repatriate, status)?;
+		let repatriated = to_repatriate.saturating_sub(gap);
+		Self::mutate_named_reserve(id, currency_id, slashed, repatriated, false);
+		if matches!(status, BalanceStatus::Reserved) {
+			Self::mutate_named_reserve(id, currency_id, beneficiary, repatriated, true);
 		}
+		Ok(value.saturating_sub(repatriated))
 	}
 }
 
@@ -536,6 +3557,99 @@ where
 	}
 }
 
+impl<T, GetCurrencyId> Stp258AssetKeepAlive<T::AccountId> for Currency<T, GetCurrencyId>
+where
+	T: Config,
+	GetCurrencyId: Get<CurrencyIdOf<T>>,
+{
+	fn transfer_keep_alive(from: &T::AccountId, to: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		Pallet::<T>::transfer_keep_alive_under(GetCurrencyId::get(), from, to, amount)
+	}
+}
+
+impl<T, GetCurrencyId> Stp258AssetTimedLockable<T::AccountId> for Currency<T, GetCurrencyId>
+where
+	T: Config,
+	GetCurrencyId: Get<CurrencyIdOf<T>>,
+{
+	fn set_lock_until(
+		lock_id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		until: Self::Moment,
+	) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyTimedLockable<T::AccountId>>::set_lock_until(
+			lock_id,
+			GetCurrencyId::get(),
+			who,
+			amount,
+			until,
+		)
+	}
+
+	fn lock_expiry(lock_id: LockIdentifier, who: &T::AccountId) -> Option<Self::Moment> {
+		<Pallet<T> as Stp258CurrencyTimedLockable<T::AccountId>>::lock_expiry(lock_id, GetCurrencyId::get(), who)
+	}
+}
+
+impl<T, GetCurrencyId> Stp258AssetLockableWithReasons<T::AccountId> for Currency<T, GetCurrencyId>
+where
+	T: Config,
+	GetCurrencyId: Get<CurrencyIdOf<T>>,
+{
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyLockableWithReasons<T::AccountId>>::set_lock_with_reasons(
+			lock_id,
+			GetCurrencyId::get(),
+			who,
+			amount,
+			reasons,
+		)
+	}
+
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyLockableWithReasons<T::AccountId>>::extend_lock_with_reasons(
+			lock_id,
+			GetCurrencyId::get(),
+			who,
+			amount,
+			reasons,
+		)
+	}
+
+	fn ensure_can_withdraw_with_reasons(
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyLockableWithReasons<T::AccountId>>::ensure_can_withdraw_with_reasons(
+			GetCurrencyId::get(),
+			who,
+			amount,
+			reasons,
+		)
+	}
+
+	fn withdraw_with_reasons(who: &T::AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyLockableWithReasons<T::AccountId>>::withdraw_with_reasons(
+			GetCurrencyId::get(),
+			who,
+			amount,
+			reasons,
+		)
+	}
+}
+
 impl<T, GetCurrencyId> Stp258AssetReservable<T::AccountId> for Currency<T, GetCurrencyId>
 where
 	T: Config,
@@ -577,6 +3691,50 @@ where
 	}
 }
 
+impl<T, GetCurrencyId> Stp258AssetNamedReservable<T::AccountId> for Currency<T, GetCurrencyId>
+where
+	T: Config,
+	GetCurrencyId: Get<CurrencyIdOf<T>>,
+{
+	fn reserved_balance_named(id: &ReserveIdentifier, who: &T::AccountId) -> Self::Balance {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::reserved_balance_named(id, GetCurrencyId::get(), who)
+	}
+
+	fn reserve_named(id: &ReserveIdentifier, who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::reserve_named(id, GetCurrencyId::get(), who, value)
+	}
+
+	fn unreserve_named(id: &ReserveIdentifier, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::unreserve_named(id, GetCurrencyId::get(), who, value)
+	}
+
+	fn slash_reserved_named(id: &ReserveIdentifier, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::slash_reserved_named(
+			id,
+			GetCurrencyId::get(),
+			who,
+			value,
+		)
+	}
+
+	fn repatriate_reserved_named(
+		id: &ReserveIdentifier,
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError> {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::repatriate_reserved_named(
+			id,
+			GetCurrencyId::get(),
+			slashed,
+			beneficiary,
+			value,
+			status,
+		)
+	}
+}
+
 pub type Stp258NativeOf<T> = Currency<T, <T as Config>::GetStp258NativeId>;
 
 /// Adapt other currency traits implementation to `Stp258Asset`.
@@ -584,6 +3742,22 @@ pub struct Stp258AssetAdapter<T, Currency, Amount, Moment>(marker::PhantomData<(
 
 type PalletBalanceOf<A, Currency> = <Currency as SetheumCurrency<A>>::Balance;
 
+fn ensure_can_withdraw_from<T, AccountId, Currency>(
+	who: &AccountId,
+	amount: PalletBalanceOf<AccountId, Currency>,
+	reasons: WithdrawReasons,
+) -> DispatchResult
+where
+	Currency: SetheumCurrency<AccountId>,
+	T: Config,
+{
+	let new_balance = Currency::free_balance(who)
+		.checked_sub(&amount)
+		.ok_or(Error::<T>::BalanceTooLow)?;
+
+	Currency::ensure_can_withdraw(who, amount, reasons, new_balance)
+}
+
 // Adapt `frame_support::traits::Currency`
 impl<T, AccountId, Currency, Amount, Moment> Stp258Asset<AccountId>
 	for Stp258AssetAdapter<T, Currency, Amount, Moment>
@@ -610,11 +3784,7 @@ where
 	}
 
 	fn ensure_can_withdraw(who: &AccountId, amount: Self::Balance) -> DispatchResult {
-		let new_balance = Self::free_balance(who)
-			.checked_sub(&amount)
-			.ok_or(Error::<T>::BalanceTooLow)?;
-
-		Currency::ensure_can_withdraw(who, amount, WithdrawReasons::all(), new_balance)
+		ensure_can_withdraw_from::<T, _, Currency>(who, amount, WithdrawReasons::all())
 	}
 
 	fn transfer(from: &AccountId, to: &AccountId, amount: Self::Balance) -> DispatchResult {
@@ -622,7 +3792,11 @@ where
 	}
 
 	fn deposit(who: &AccountId, amount: Self::Balance) -> DispatchResult {
-		let _ = Currency::deposit_creating(who, amount);
+		if amount.is_zero() {
+			return Ok(());
+		}
+		let deposited = Currency::deposit_creating(who, amount);
+		ensure!(!deposited.peek().is_zero(), Error::<T>::BelowExistentialDeposit);
 		Ok(())
 	}
 
@@ -640,6 +3814,18 @@ where
 	}
 }
 
+// Adapt `frame_support::traits::Currency`
+impl<T, AccountId, Currency, Amount, Moment> Stp258AssetKeepAlive<AccountId>
+	for Stp258AssetAdapter<T, Currency, Amount, Moment>
+where
+	Currency: SetheumCurrency<AccountId>,
+	T: Config,
+{
+	fn transfer_keep_alive(from: &AccountId, to: &AccountId, amount: Self::Balance) -> DispatchResult {
+		Currency::transfer(from, to, amount, ExistenceRequirement::KeepAlive)
+	}
+}
+
 // Adapt `frame_support::traits::Currency`
 impl<T, AccountId, Currency, Amount, Moment> Stp258AssetExtended<AccountId>
 	for Stp258AssetAdapter<T, Currency, Amount, Moment>
@@ -696,6 +3882,46 @@ where
 	}
 }
 
+// Adapt `frame_support::traits::LockableCurrency` with custom `WithdrawReasons`
+impl<T, AccountId, Currency, Amount, Moment> Stp258AssetLockableWithReasons<AccountId>
+	for Stp258AssetAdapter<T, Currency, Amount, Moment>
+where
+	Currency: SetheumLockableCurrency<AccountId>,
+	T: Config,
+{
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		Currency::set_lock(lock_id, who, amount, reasons);
+		Ok(())
+	}
+
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		Currency::extend_lock(lock_id, who, amount, reasons);
+		Ok(())
+	}
+
+	fn ensure_can_withdraw_with_reasons(
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		ensure_can_withdraw_from::<T, _, Currency>(who, amount, reasons)
+	}
+
+	fn withdraw_with_reasons(who: &AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult {
+		Currency::withdraw(who, amount, reasons, ExistenceRequirement::AllowDeath).map(|_| ())
+	}
+}
+
 // Adapt `frame_support::traits::ReservableCurrency`
 impl<T, AccountId, Currency, Amount, Moment> Stp258AssetReservable<AccountId>
 	for Stp258AssetAdapter<T, Currency, Amount, Moment>
@@ -736,15 +3962,123 @@ where
 
 impl<T: Config> MergeAccount<T::AccountId> for Pallet<T> {
 	fn merge_account(source: &T::AccountId, dest: &T::AccountId) -> DispatchResult {
-		with_transaction_result(|| {
-			// transfer non-native free to dest
-			T::Stp258Currency::merge_account(source, dest)?;
+		Self::do_merge_account(source, dest).map(|_| ())
+	}
+}
 
-			// unreserve all reserved currency
-			T::Stp258Native::unreserve(source, T::Stp258Native::reserved_balance(source));
+/// Quotes the spot price of the native currency in the liquidity pool of
+/// each stable currency.
+///
+/// The spot price is that of a single block and can be moved at will by a
+/// large enough swap, undone in the next block. As the `PriceProvider` the
+/// stable currencies are serped by, it lets such a swap trigger a supply
+/// adjustment; prefer an oracle or a time-weighted average there, or keep
+/// the supply caps tight.
+pub struct SpotPriceProvider<T>(marker::PhantomData<T>);
 
-			// transfer all free to dest
-			T::Stp258Native::transfer(source, dest, T::Stp258Native::free_balance(source))
-		})
+impl<T: Config> PriceProvider<CurrencyIdOf<T>, BalanceOf<T>> for SpotPriceProvider<T> {
+	fn get_price(currency_id: CurrencyIdOf<T>) -> Option<BalanceOf<T>> {
+		Pallet::<T>::spot_price(currency_id)
+	}
+}
+
+/// Adjusts the supply by the relative deviation of the market price from the
+/// peg, so a market price 10% above the peg expands the supply by 10%.
+pub struct ProportionalAdjuster;
+
+impl<CurrencyId, Balance> SupplyAdjuster<CurrencyId, Balance> for ProportionalAdjuster
+where
+	Balance: AtLeast32BitUnsigned + Copy,
+{
+	fn adjustment(
+		_currency_id: CurrencyId,
+		total_issuance: Balance,
+		peg: Balance,
+		market_price: Balance,
+	) -> Option<SupplyAdjustment<Balance>> {
+		if peg.is_zero() || market_price == peg {
+			None
+		} else if market_price > peg {
+			let expand_by = Perbill::from_rational_approximation(market_price - peg, peg).mul_floor(total_issuance);
+			Some(SupplyAdjustment::Expand(expand_by))
+		} else {
+			let contract_by = Perbill::from_rational_approximation(peg - market_price, peg).mul_floor(total_issuance);
+			Some(SupplyAdjustment::Contract(contract_by))
+		}
+	}
+}
+
+/// Adjusts the supply with a PID controller over the relative deviation of
+/// the market price from the peg, keeping its state in `PidStates`. The state
+/// is only updated once an adjustment succeeds, so failed serps do not wind
+/// up the integral.
+///
+/// `Kp`, `Ki` and `Kd` are the proportional, integral and derivative gains.
+pub struct PidAdjuster<T, Kp, Ki, Kd>(marker::PhantomData<(T, Kp, Ki, Kd)>);
+
+impl<T, Kp, Ki, Kd> PidAdjuster<T, Kp, Ki, Kd>
+where
+	T: Config,
+	Kp: Get<FixedI128>,
+	Ki: Get<FixedI128>,
+	Kd: Get<FixedI128>,
+{
+	/// The state of `currency_id` after an adjustment at `market_price`, and
+	/// the controller output of that adjustment. `peg` must not be zero.
+	fn step(currency_id: CurrencyIdOf<T>, peg: BalanceOf<T>, market_price: BalanceOf<T>) -> (PidState, FixedI128) {
+		let peg: u128 = peg.unique_saturated_into();
+		let market_price: u128 = market_price.unique_saturated_into();
+		let error = if market_price >= peg {
+			FixedI128::saturating_from_rational(market_price - peg, peg)
+		} else {
+			FixedI128::zero().saturating_sub(FixedI128::saturating_from_rational(peg - market_price, peg))
+		};
+
+		let mut state = PidStates::<T>::get(currency_id);
+		state.integral = state.integral.saturating_add(error);
+		let derivative = error.saturating_sub(state.last_error);
+		state.last_error = error;
+
+		let output = Kp::get()
+			.saturating_mul(error)
+			.saturating_add(Ki::get().saturating_mul(state.integral))
+			.saturating_add(Kd::get().saturating_mul(derivative));
+		(state, output)
+	}
+}
+
+impl<T, Kp, Ki, Kd> SupplyAdjuster<CurrencyIdOf<T>, BalanceOf<T>> for PidAdjuster<T, Kp, Ki, Kd>
+where
+	T: Config,
+	Kp: Get<FixedI128>,
+	Ki: Get<FixedI128>,
+	Kd: Get<FixedI128>,
+{
+	fn adjustment(
+		currency_id: CurrencyIdOf<T>,
+		total_issuance: BalanceOf<T>,
+		peg: BalanceOf<T>,
+		market_price: BalanceOf<T>,
+	) -> Option<SupplyAdjustment<BalanceOf<T>>> {
+		if peg.is_zero() {
+			return None;
+		}
+
+		let (_, output) = Self::step(currency_id, peg, market_price);
+		let total_issuance: u128 = total_issuance.unique_saturated_into();
+		let amount = BalanceOf::<T>::unique_saturated_from(output.saturating_abs().saturating_mul_int(total_issuance));
+		if amount.is_zero() {
+			None
+		} else if output.is_positive() {
+			Some(SupplyAdjustment::Expand(amount))
+		} else {
+			Some(SupplyAdjustment::Contract(amount))
+		}
+	}
+
+	fn on_adjusted(currency_id: CurrencyIdOf<T>, peg: BalanceOf<T>, market_price: BalanceOf<T>) {
+		if !peg.is_zero() {
+			PidStates::<T>::insert(currency_id, Self::step(currency_id, peg, market_price).0);
+		}
 	}
 }
@@ -3,9 +3,11 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{construct_runtime, parameter_types};
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types};
+use frame_system::{EnsureOneOf, EnsureRoot, EnsureSignedBy};
 use serp_traits::parameter_type_with_key;
 use sp_core::H256;
+use std::{cell::RefCell, collections::HashMap};
 use sp_runtime::{
 	testing::Header,
 	traits::{AccountIdConversion, IdentityLookup},
@@ -44,12 +46,19 @@ impl frame_system::Config for Runtime {
 	type SS58Prefix = ();
 }
 
-type CurrencyId = u32;
-type Balance = u64;
-type Blocknumber = u64;
+pub type CurrencyId = u32;
+pub type Balance = u64;
+pub type Blocknumber = u64;
 
-parameter_types! {
-	pub const ExistentialDeposit: u64 = 1;
+thread_local! {
+	static EXISTENTIAL_DEPOSIT: RefCell<Balance> = RefCell::new(1);
+}
+
+pub struct ExistentialDeposit;
+impl Get<Balance> for ExistentialDeposit {
+	fn get() -> Balance {
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+	}
 }
 
 impl pallet_balances::Config for Runtime {
@@ -62,9 +71,13 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = ();
 }
 
+thread_local! {
+	static STABLE_EXISTENTIAL_DEPOSIT: RefCell<Balance> = RefCell::new(0);
+}
+
 parameter_type_with_key! {
 	pub ExistentialDeposits: |currency_id: CurrencyId| -> Balance {
-		Default::default()
+		STABLE_EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
 	};
 }
 
@@ -125,6 +138,51 @@ pub const SETT: CurrencyId = 2;
 pub const JUSD: CurrencyId = 3;
 
 pub const ADJUSTMENT_FREQUENCY: Blocknumber = 10;
+pub const CAP_WINDOW: Blocknumber = 100;
+
+pub const MAX_SUPPLY_HISTORY: u32 = 3;
+pub const MAX_TRANSFER_BATCH_SIZE: u32 = 4;
+pub const MAX_LOCK_EXPIRIES: u32 = 2;
+pub const MAX_VESTING_SCHEDULES: u32 = 2;
+pub const MAX_AUCTION_BIDS: u32 = 2;
+pub const MAX_BOND_REDEMPTIONS: u32 = 3;
+pub const MAX_OPEN_ORDERS: u32 = 2;
+pub const MAX_ORDER_MATCHES: u32 = 3;
+pub const MAX_BOOK_ORDERS: u32 = 3;
+
+parameter_types! {
+	pub const CapWindow: Blocknumber = CAP_WINDOW;
+	pub const MaxSupplyHistory: u32 = MAX_SUPPLY_HISTORY;
+	pub const MaxTransferBatchSize: u32 = MAX_TRANSFER_BATCH_SIZE;
+	pub const MaxLockExpiries: u32 = MAX_LOCK_EXPIRIES;
+	pub const MaxVestingSchedules: u32 = MAX_VESTING_SCHEDULES;
+	pub const AuctionStartPremium: Perbill = Perbill::from_percent(50);
+	pub const AuctionFloorDiscount: Perbill = Perbill::from_percent(50);
+	pub const MaxAuctionBids: u32 = MAX_AUCTION_BIDS;
+	pub const BondPremium: Perbill = Perbill::from_percent(10);
+	pub const MaxBondRedemptions: u32 = MAX_BOND_REDEMPTIONS;
+	pub const TreasuryAccount: AccountId = TREASURY;
+	pub const StakersAccount: AccountId = STAKERS;
+	pub const ReserveBufferAccount: AccountId = RESERVE_BUFFER;
+	pub const PoolModuleId: ModuleId = ModuleId(*b"set/pool");
+	pub const SwapFee: Perbill = Perbill::from_percent(1);
+	pub const MaxOpenOrders: u32 = MAX_OPEN_ORDERS;
+	pub const MaxOrderMatches: u32 = MAX_ORDER_MATCHES;
+	pub const MaxBookOrders: u32 = MAX_BOOK_ORDERS;
+}
+
+pub const SETT_INFO: StableCurrencyInfo<Balance> = StableCurrencyInfo {
+	peg: 10_000,
+	base_unit: 10_000,
+	enabled: true,
+	max_expansion: 1_000 * 10_000,
+};
+pub const JUSD_INFO: StableCurrencyInfo<Balance> = StableCurrencyInfo {
+	peg: 1_000,
+	base_unit: 1_000,
+	enabled: true,
+	max_expansion: 1_000 * 1_000,
+};
 
 parameter_types! {
 	pub const GetStp258NativeId: CurrencyId = DNAR;
@@ -140,14 +198,124 @@ impl stp258_standard::Config for Runtime {
 pub type Stp258Native = Stp258NativeOf<Runtime>;
 pub type AdaptedStp258Asset = Stp258AssetAdapter<Runtime, PalletBalances, i64, u64>;
 
+ord_parameter_types! {
+	pub const CouncilAccount: AccountId = COUNCIL;
+}
+
+thread_local! {
+	static PRICES: RefCell<HashMap<CurrencyId, Balance>> = RefCell::new(HashMap::new());
+}
+
+/// An in-memory price feed.
+pub struct MockPriceProvider;
+impl MockPriceProvider {
+	pub fn set_price(currency_id: CurrencyId, price: Balance) {
+		PRICES.with(|v| v.borrow_mut().insert(currency_id, price));
+	}
+}
+impl PriceProvider<CurrencyId, Balance> for MockPriceProvider {
+	fn get_price(currency_id: CurrencyId) -> Option<Balance> {
+		PRICES.with(|v| v.borrow().get(&currency_id).copied())
+	}
+}
+
+thread_local! {
+	static EXPANSION_VESTING: RefCell<Option<(Blocknumber, Blocknumber)>> = RefCell::new(None);
+}
+
+pub struct ExpansionVesting;
+impl Get<Option<(Blocknumber, Blocknumber)>> for ExpansionVesting {
+	fn get() -> Option<(Blocknumber, Blocknumber)> {
+		EXPANSION_VESTING.with(|v| *v.borrow())
+	}
+}
+
+thread_local! {
+	static AUCTION_DURATION: RefCell<Blocknumber> = RefCell::new(0);
+}
+
+pub struct AuctionDuration;
+impl Get<Blocknumber> for AuctionDuration {
+	fn get() -> Blocknumber {
+		AUCTION_DURATION.with(|v| *v.borrow())
+	}
+}
+
+thread_local! {
+	static BOND_DURATION: RefCell<Blocknumber> = RefCell::new(0);
+}
+
+pub struct BondDuration;
+impl Get<Blocknumber> for BondDuration {
+	fn get() -> Blocknumber {
+		BOND_DURATION.with(|v| *v.borrow())
+	}
+}
+
 impl Config for Runtime {
 	type Event = Event;
 	type Stp258Currency = Stp258Serp;
 	type Stp258Native = AdaptedStp258Asset;
 	type GetStp258NativeId = GetStp258NativeId;
+	type PriceProvider = MockPriceProvider;
+	type AdjustmentFrequency = AdjustmentFrequency;
+	type SupplyAdjuster = ProportionalAdjuster;
+	type CapWindow = CapWindow;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type UpdateOrigin = EnsureOneOf<AccountId, EnsureRoot<AccountId>, EnsureSignedBy<CouncilAccount, AccountId>>;
+	type SerpOrigin = EnsureRoot<AccountId>;
+	type MaxSupplyHistory = MaxSupplyHistory;
+	type MaxTransferBatchSize = MaxTransferBatchSize;
+	type MaxLockExpiries = MaxLockExpiries;
+	type SerperAccount = GetSerperAcc;
+	type ExpansionVesting = ExpansionVesting;
+	type MaxVestingSchedules = MaxVestingSchedules;
+	type AuctionDuration = AuctionDuration;
+	type AuctionStartPremium = AuctionStartPremium;
+	type AuctionFloorDiscount = AuctionFloorDiscount;
+	type MaxAuctionBids = MaxAuctionBids;
+	type BondDuration = BondDuration;
+	type BondPremium = BondPremium;
+	type MaxBondRedemptions = MaxBondRedemptions;
+	type TreasuryAccount = TreasuryAccount;
+	type StakersAccount = StakersAccount;
+	type ReserveBufferAccount = ReserveBufferAccount;
+	type PoolModuleId = PoolModuleId;
+	type SwapFee = SwapFee;
+	type MaxOpenOrders = MaxOpenOrders;
+	type MaxOrderMatches = MaxOrderMatches;
+	type MaxBookOrders = MaxBookOrders;
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub PidKp: FixedI128 = FixedI128::saturating_from_integer(1);
+	pub PidKi: FixedI128 = FixedI128::saturating_from_rational(1, 2);
+	pub PidKd: FixedI128 = FixedI128::saturating_from_rational(1, 4);
+}
+
+pub type MockPidAdjuster = PidAdjuster<Runtime, PidKp, PidKi, PidKd>;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl benchmarking::Config for Runtime {
+	fn stable_currency_id() -> CurrencyId {
+		JUSD
+	}
+
+	fn stable_currency_ids() -> Vec<CurrencyId> {
+		vec![SETT, JUSD]
+	}
+
+	fn set_price(currency_id: CurrencyId, price: Balance) {
+		MockPriceProvider::set_price(currency_id, price);
+	}
+
+	fn setup_contract_supply(currency_id: CurrencyId, contract_by: Balance) {
+		Stp258Serp::deposit(currency_id, &SERPER, contract_by).unwrap();
+		Stp258Serp::reserve(currency_id, &SERPER, contract_by).unwrap();
+	}
+}
+
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
 type Block = frame_system::mocking::MockBlock<Runtime>;
 
@@ -158,7 +326,7 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Module, Call, Storage, Config, Event<T>},
-		Market: market::{Module, Call, Event<T>},
+		Market: market::{Module, Call, Storage, Config<T>, Event<T>},
 		Stp258Standard: stp258_standard::{Module, Call, Event<T>},
 		Stp258Serp: stp258_serp::{Module, Storage, Event<T>, Config<T>},
 		PalletBalances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
@@ -169,15 +337,29 @@ pub const ALICE: AccountId = AccountId32::new([0u8; 32]);
 pub const BOB: AccountId = AccountId32::new([1u8; 32]);
 pub const SERPER: AccountId = AccountId32::new([3u8; 32]);
 pub const SETTPAY: AccountId = AccountId32::new([4u8; 32]);
+pub const COUNCIL: AccountId = AccountId32::new([5u8; 32]);
+pub const TREASURY: AccountId = AccountId32::new([6u8; 32]);
+pub const STAKERS: AccountId = AccountId32::new([7u8; 32]);
+pub const RESERVE_BUFFER: AccountId = AccountId32::new([8u8; 32]);
 
 pub struct ExtBuilder {
 	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+	existential_deposit: Balance,
+	stable_existential_deposit: Balance,
+	expansion_vesting: Option<(Blocknumber, Blocknumber)>,
+	auction_duration: Blocknumber,
+	bond_duration: Blocknumber,
 }
 
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			endowed_accounts: vec![],
+			existential_deposit: 1,
+			stable_existential_deposit: 0,
+			expansion_vesting: None,
+			auction_duration: 0,
+			bond_duration: 0,
 		}
 	}
 }
@@ -188,6 +370,31 @@ impl ExtBuilder {
 		self
 	}
 
+	pub fn existential_deposit(mut self, existential_deposit: Balance) -> Self {
+		self.existential_deposit = existential_deposit;
+		self
+	}
+
+	pub fn stable_existential_deposit(mut self, stable_existential_deposit: Balance) -> Self {
+		self.stable_existential_deposit = stable_existential_deposit;
+		self
+	}
+
+	pub fn expansion_vesting(mut self, cliff: Blocknumber, duration: Blocknumber) -> Self {
+		self.expansion_vesting = Some((cliff, duration));
+		self
+	}
+
+	pub fn auction_duration(mut self, auction_duration: Blocknumber) -> Self {
+		self.auction_duration = auction_duration;
+		self
+	}
+
+	pub fn bond_duration(mut self, bond_duration: Blocknumber) -> Self {
+		self.bond_duration = bond_duration;
+		self
+	}
+
 	pub fn one_hundred_for_alice_n_bob_n_serper_n_settpay(self) -> Self {
 		self.balances(vec![
 			(ALICE, DNAR, 100), 
@@ -206,6 +413,13 @@ impl ExtBuilder {
 	}
 
 	pub fn build(self) -> sp_io::TestExternalities {
+		PRICES.with(|v| v.borrow_mut().clear());
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+		STABLE_EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.stable_existential_deposit);
+		EXPANSION_VESTING.with(|v| *v.borrow_mut() = self.expansion_vesting);
+		AUCTION_DURATION.with(|v| *v.borrow_mut() = self.auction_duration);
+		BOND_DURATION.with(|v| *v.borrow_mut() = self.bond_duration);
+
 		let mut t = frame_system::GenesisConfig::default()
 			.build_storage::<Runtime>()
 			.unwrap();
@@ -232,6 +446,12 @@ impl ExtBuilder {
 		.assimilate_storage(&mut t)
 		.unwrap();
 
+		market::GenesisConfig::<Runtime> {
+			stable_currencies: vec![(SETT, SETT_INFO), (JUSD, JUSD_INFO)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
 		t.into()
 	}
 }
@@ -3,7 +3,10 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OnFinalize, OnInitialize},
+};
 use mock::{Event, *};
 use sp_runtime::traits::BadOrigin;
 
@@ -243,3 +246,2143 @@ fn call_event_should_work() {
 		});
 }
 
+
+#[test]
+fn market_expand_supply_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_eq!(Market::total_issuance(JUSD), 440 * 1_000);
+
+			let serped_up_event = Event::market(crate::Event::SerpedUpSupply(JUSD, 40 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == serped_up_event));
+		});
+}
+
+#[test]
+fn market_contract_supply_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Market::update_balance(Origin::root(), SERPER, JUSD, 1_000 * 1_000));
+			assert_ok!(Stp258Serp::reserve(JUSD, &SERPER, 1_000 * 1_000));
+			assert_ok!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_eq!(Market::total_issuance(JUSD), 1_360 * 1_000);
+
+			let serped_down_event = Event::market(crate::Event::SerpedDownSupply(JUSD, 40 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == serped_down_event));
+		});
+}
+
+#[test]
+fn expand_supply_fails_with_unrecognised_currencies() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(SETT, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::UnrecognisedNativeCurrency
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(JUSD, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::UnrecognisedNativeCurrency
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(SETT, DNAR, 40 * 1_000, 4_000),
+				Error::<Runtime>::UnrecognisedNativeCurrency
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, DNAR, 40 * 1_000, 4_000),
+				Error::<Runtime>::NativeCurrencyCannotBeSerped
+			);
+			assert_eq!(Market::total_issuance(JUSD), 400 * 1_000);
+			assert!(System::events().is_empty());
+		});
+}
+
+#[test]
+fn contract_supply_fails_with_unrecognised_currencies() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::contract_supply(SETT, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::UnrecognisedNativeCurrency
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::contract_supply(JUSD, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::UnrecognisedNativeCurrency
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::contract_supply(SETT, DNAR, 40 * 1_000, 4_000),
+				Error::<Runtime>::UnrecognisedNativeCurrency
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::contract_supply(DNAR, DNAR, 40 * 1_000, 4_000),
+				Error::<Runtime>::NativeCurrencyCannotBeSerped
+			);
+			assert_eq!(Market::total_issuance(JUSD), 400 * 1_000);
+			assert!(System::events().is_empty());
+		});
+}
+
+#[test]
+fn serp_fails_for_unregistered_or_disabled_currency() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::deregister_stable_currency(Origin::root(), JUSD));
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::StableCurrencyNotRegistered
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::StableCurrencyNotRegistered
+			);
+
+			assert_ok!(Market::update_stable_currency(
+				Origin::root(),
+				SETT,
+				StableCurrencyInfo {
+					enabled: false,
+					..SETT_INFO
+				}
+			));
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, SETT, 40 * 10_000, 4_000),
+				Error::<Runtime>::StableCurrencyDisabled
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::contract_supply(DNAR, SETT, 40 * 10_000, 4_000),
+				Error::<Runtime>::StableCurrencyDisabled
+			);
+		});
+}
+
+#[test]
+fn expand_supply_fails_above_max_expansion() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, JUSD_INFO.max_expansion + 1, 4_000),
+				Error::<Runtime>::ExceedsMaxExpansion
+			);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(
+				DNAR,
+				JUSD,
+				JUSD_INFO.max_expansion,
+				4_000
+			));
+		});
+}
+
+#[test]
+fn stable_currency_registry_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		const NEW_SETT: CurrencyId = 4;
+
+		assert_eq!(Market::stable_currencies(NEW_SETT), None);
+		assert_ok!(Market::register_stable_currency(Origin::root(), NEW_SETT, JUSD_INFO));
+		assert_eq!(Market::stable_currencies(NEW_SETT), Some(JUSD_INFO));
+		assert_eq!(Market::base_unit(NEW_SETT), JUSD_INFO.base_unit);
+		let registered_event = Event::market(crate::Event::StableCurrencyRegistered(NEW_SETT, JUSD_INFO));
+		assert!(System::events().iter().any(|record| record.event == registered_event));
+
+		assert_noop!(
+			Market::register_stable_currency(Origin::root(), NEW_SETT, JUSD_INFO),
+			Error::<Runtime>::StableCurrencyAlreadyRegistered
+		);
+		assert_noop!(
+			Market::register_stable_currency(Origin::root(), DNAR, JUSD_INFO),
+			Error::<Runtime>::NativeCurrencyCannotBeSerped
+		);
+
+		assert_ok!(Market::update_stable_currency(Origin::root(), NEW_SETT, SETT_INFO));
+		assert_eq!(Market::stable_currencies(NEW_SETT), Some(SETT_INFO));
+		let updated_event = Event::market(crate::Event::StableCurrencyUpdated(NEW_SETT, SETT_INFO));
+		assert!(System::events().iter().any(|record| record.event == updated_event));
+
+		assert_ok!(Market::deregister_stable_currency(Origin::root(), NEW_SETT));
+		assert_eq!(Market::stable_currencies(NEW_SETT), None);
+		let deregistered_event = Event::market(crate::Event::StableCurrencyDeregistered(NEW_SETT));
+		assert!(System::events().iter().any(|record| record.event == deregistered_event));
+
+		assert_noop!(
+			Market::update_stable_currency(Origin::root(), NEW_SETT, SETT_INFO),
+			Error::<Runtime>::StableCurrencyNotRegistered
+		);
+		assert_noop!(
+			Market::deregister_stable_currency(Origin::root(), NEW_SETT),
+			Error::<Runtime>::StableCurrencyNotRegistered
+		);
+	});
+}
+
+#[test]
+fn stable_currency_registry_fails_if_not_root_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Market::register_stable_currency(Some(ALICE).into(), 4, JUSD_INFO),
+			BadOrigin
+		);
+		assert_noop!(
+			Market::update_stable_currency(Some(ALICE).into(), JUSD, SETT_INFO),
+			BadOrigin
+		);
+		assert_noop!(
+			Market::deregister_stable_currency(Some(ALICE).into(), JUSD),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn on_initialize_expands_supply_above_peg() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			MockPriceProvider::set_price(JUSD, 1_100);
+
+			Market::on_initialize(ADJUSTMENT_FREQUENCY - 1);
+			assert_eq!(Market::total_issuance(JUSD), 400 * 1_000);
+
+			Market::on_initialize(ADJUSTMENT_FREQUENCY);
+			assert_eq!(Market::total_issuance(JUSD), 440 * 1_000);
+
+			let serped_up_event = Event::market(crate::Event::SerpedUpSupply(JUSD, 40 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == serped_up_event));
+		});
+}
+
+#[test]
+fn on_initialize_clamps_adjustments_to_supply_cap_room() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(ADJUSTMENT_FREQUENCY);
+			let cap = SupplyCap {
+				absolute: 1_000 * 1_000,
+				ratio: Perbill::from_percent(100),
+			};
+			assert_ok!(Market::set_supply_caps(
+				Origin::root(),
+				JUSD,
+				Some(SupplyCaps {
+					per_call: cap,
+					per_block: SupplyCap {
+						absolute: 50 * 1_000,
+						..cap
+					},
+					per_window: cap,
+				})
+			));
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+			MockPriceProvider::set_price(JUSD, 1_100);
+
+			// The adjuster asks for 42_000, but only 30_000 is left under the block cap.
+			Market::on_initialize(ADJUSTMENT_FREQUENCY);
+			assert_eq!(Market::total_issuance(JUSD), 450 * 1_000);
+			let serped_up_event = Event::market(crate::Event::SerpedUpSupply(JUSD, 30 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == serped_up_event));
+
+			// With no room left the adjustment is skipped.
+			Market::on_initialize(ADJUSTMENT_FREQUENCY);
+			assert_eq!(Market::total_issuance(JUSD), 450 * 1_000);
+			assert!(!System::events()
+				.iter()
+				.any(|record| matches!(record.event, Event::market(crate::Event::SupplyCapBreached(..)))));
+		});
+}
+
+#[test]
+fn on_initialize_contracts_supply_below_peg() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::update_balance(Origin::root(), SERPER, JUSD, 1_000 * 1_000));
+			assert_ok!(Stp258Serp::reserve(JUSD, &SERPER, 1_000 * 1_000));
+			MockPriceProvider::set_price(JUSD, 900);
+
+			Market::on_initialize(ADJUSTMENT_FREQUENCY);
+			assert_eq!(Market::total_issuance(JUSD), 1_260 * 1_000);
+
+			let serped_down_event = Event::market(crate::Event::SerpedDownSupply(JUSD, 140 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == serped_down_event));
+		});
+}
+
+#[test]
+fn on_initialize_skips_pegged_disabled_and_deregistered_currencies() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			MockPriceProvider::set_price(SETT, 10_000);
+			MockPriceProvider::set_price(JUSD, 1_100);
+			assert_ok!(Market::update_stable_currency(
+				Origin::root(),
+				JUSD,
+				StableCurrencyInfo {
+					enabled: false,
+					..JUSD_INFO
+				}
+			));
+
+			Market::on_initialize(ADJUSTMENT_FREQUENCY);
+			assert_eq!(Market::total_issuance(SETT), 400 * 10_000);
+			assert_eq!(Market::total_issuance(JUSD), 400 * 1_000);
+
+			MockPriceProvider::set_price(SETT, 11_000);
+			assert_ok!(Market::deregister_stable_currency(Origin::root(), SETT));
+			Market::on_initialize(2 * ADJUSTMENT_FREQUENCY);
+			assert_eq!(Market::total_issuance(SETT), 400 * 10_000);
+		});
+}
+
+#[test]
+fn proportional_adjuster_should_work() {
+	assert_eq!(
+		<ProportionalAdjuster as SupplyAdjuster<CurrencyId, Balance>>::adjustment(JUSD, 400_000, 1_000, 1_100),
+		Some(SupplyAdjustment::Expand(40_000))
+	);
+	assert_eq!(
+		<ProportionalAdjuster as SupplyAdjuster<CurrencyId, Balance>>::adjustment(JUSD, 400_000, 1_000, 750),
+		Some(SupplyAdjustment::Contract(100_000))
+	);
+	assert_eq!(
+		<ProportionalAdjuster as SupplyAdjuster<CurrencyId, Balance>>::adjustment(JUSD, 400_000, 1_000, 1_000),
+		None
+	);
+	assert_eq!(
+		<ProportionalAdjuster as SupplyAdjuster<CurrencyId, Balance>>::adjustment(JUSD, 400_000, 0, 1_000),
+		None
+	);
+}
+
+#[test]
+fn pid_adjuster_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(Market::pid_states(JUSD), PidState::default());
+
+		// error 0.1: 0.1 + 0.5 * 0.1 + 0.25 * 0.1
+		assert_eq!(
+			MockPidAdjuster::adjustment(JUSD, 400_000, 1_000, 1_100),
+			Some(SupplyAdjustment::Expand(70_000))
+		);
+		// the state is only committed once the adjustment is made
+		assert_eq!(Market::pid_states(JUSD), PidState::default());
+		MockPidAdjuster::on_adjusted(JUSD, 1_000, 1_100);
+		assert_eq!(
+			Market::pid_states(JUSD),
+			PidState {
+				integral: FixedI128::saturating_from_rational(1, 10),
+				last_error: FixedI128::saturating_from_rational(1, 10),
+			}
+		);
+
+		// error 0: 0 + 0.5 * 0.1 - 0.25 * 0.1
+		assert_eq!(
+			MockPidAdjuster::adjustment(JUSD, 400_000, 1_000, 1_000),
+			Some(SupplyAdjustment::Expand(10_000))
+		);
+		MockPidAdjuster::on_adjusted(JUSD, 1_000, 1_000);
+
+		// error -0.1: -0.1 + 0.5 * 0 - 0.25 * 0.1
+		assert_eq!(
+			MockPidAdjuster::adjustment(JUSD, 400_000, 1_000, 900),
+			Some(SupplyAdjustment::Contract(50_000))
+		);
+		MockPidAdjuster::on_adjusted(JUSD, 1_000, 900);
+		assert_eq!(Market::pid_states(JUSD).integral, FixedI128::zero());
+
+		// the state of other currencies is untouched
+		assert_eq!(Market::pid_states(SETT), PidState::default());
+
+		// deregistering clears the state
+		assert_ok!(Market::deregister_stable_currency(Origin::root(), JUSD));
+		assert_eq!(Market::pid_states(JUSD), PidState::default());
+	});
+}
+
+#[test]
+fn supply_caps_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			let caps = SupplyCaps {
+				per_call: SupplyCap {
+					absolute: 30 * 1_000,
+					ratio: Perbill::from_percent(5),
+				},
+				per_block: SupplyCap {
+					absolute: 50 * 1_000,
+					ratio: Perbill::from_percent(100),
+				},
+				per_window: SupplyCap {
+					absolute: 70 * 1_000,
+					ratio: Perbill::from_percent(100),
+				},
+			};
+			assert_ok!(Market::set_supply_caps(Origin::root(), JUSD, Some(caps)));
+			assert_eq!(Market::supply_caps(JUSD), Some(caps));
+			let updated_event = Event::market(crate::Event::SupplyCapsUpdated(JUSD, Some(caps)));
+			assert!(System::events().iter().any(|record| record.event == updated_event));
+
+			// 5% of the 400_000 total issuance is below the absolute per call cap.
+			assert_eq!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 21 * 1_000, 4_000),
+				Err(Error::<Runtime>::ExceedsCallCap.into())
+			);
+			let breached_event = Event::market(crate::Event::SupplyCapBreached(
+				JUSD,
+				SupplyAdjustment::Expand(21 * 1_000),
+			));
+			assert!(System::events().iter().any(|record| record.event == breached_event));
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+			assert_eq!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000),
+				Err(Error::<Runtime>::ExceedsBlockCap.into())
+			);
+			assert_eq!(Market::total_issuance(JUSD), 440 * 1_000);
+
+			System::set_block_number(2);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+			assert_eq!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000),
+				Err(Error::<Runtime>::ExceedsWindowCap.into())
+			);
+			assert_eq!(Market::total_issuance(JUSD), 460 * 1_000);
+
+			System::set_block_number(CAP_WINDOW + 1);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+			assert_eq!(Market::total_issuance(JUSD), 480 * 1_000);
+
+			assert_ok!(Market::set_supply_caps(Origin::root(), JUSD, None));
+			assert_eq!(Market::supply_caps(JUSD), None);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 100 * 1_000, 4_000));
+		});
+}
+
+#[test]
+fn supply_caps_count_expansion_and_contraction_separately() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::update_balance(Origin::root(), SERPER, JUSD, 1_000 * 1_000));
+			assert_ok!(Stp258Serp::reserve(JUSD, &SERPER, 1_000 * 1_000));
+			let cap = SupplyCap {
+				absolute: 40 * 1_000,
+				ratio: Perbill::from_percent(100),
+			};
+			assert_ok!(Market::set_supply_caps(
+				Origin::root(),
+				JUSD,
+				Some(SupplyCaps {
+					per_call: cap,
+					per_block: cap,
+					per_window: cap,
+				})
+			));
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_ok!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_eq!(
+				<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 1, 4_000),
+				Err(Error::<Runtime>::ExceedsBlockCap.into())
+			);
+			assert_eq!(
+				Market::supply_adjustment_tallies(JUSD).buckets,
+				vec![SupplyAdjustmentBucket {
+					block: 1,
+					expanded: 40 * 1_000,
+					contracted: 40 * 1_000,
+				}]
+			);
+		});
+}
+
+#[test]
+fn supply_cap_window_should_roll() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			let cap = SupplyCap {
+				absolute: 100 * 1_000,
+				ratio: Perbill::from_percent(100),
+			};
+			assert_ok!(Market::set_supply_caps(
+				Origin::root(),
+				JUSD,
+				Some(SupplyCaps {
+					per_call: cap,
+					per_block: cap,
+					per_window: SupplyCap {
+						absolute: 40 * 1_000,
+						..cap
+					},
+				})
+			));
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+			System::set_block_number(CAP_WINDOW);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+
+			// only the expansion of block 1 has left the window
+			System::set_block_number(CAP_WINDOW + 1);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+			assert_eq!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 1, 4_000),
+				Err(Error::<Runtime>::ExceedsWindowCap.into())
+			);
+			assert_eq!(Market::supply_adjustment_tallies(JUSD).buckets.len(), 2);
+
+			System::set_block_number(2 * CAP_WINDOW);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 20 * 1_000, 4_000));
+			assert_eq!(Market::total_issuance(JUSD), 480 * 1_000);
+		});
+}
+
+#[test]
+fn set_supply_caps_should_fail() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Market::set_supply_caps(Some(ALICE).into(), JUSD, None),
+			BadOrigin
+		);
+		assert_noop!(
+			Market::set_supply_caps(Origin::root(), DNAR, None),
+			Error::<Runtime>::StableCurrencyNotRegistered
+		);
+	});
+}
+
+#[test]
+fn pause_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Market::pause(Origin::root(), JUSD));
+			assert!(Market::paused_currencies(JUSD));
+			let paused_event = Event::market(crate::Event::CurrencyPaused(JUSD));
+			assert!(System::events().iter().any(|record| record.event == paused_event));
+
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_noop!(
+				Market::transfer(Some(ALICE).into(), BOB, JUSD, 10 * 1_000),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_noop!(
+				Market::update_balance(Origin::root(), ALICE, JUSD, 10 * 1_000),
+				Error::<Runtime>::CurrencyPaused
+			);
+
+			// other currencies keep working
+			assert_ok!(Market::transfer(Some(ALICE).into(), BOB, SETT, 10 * 10_000));
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, SETT, 40 * 10_000, 4_000));
+
+			assert_ok!(Market::unpause(Origin::root(), JUSD));
+			assert!(!Market::paused_currencies(JUSD));
+			let unpaused_event = Event::market(crate::Event::CurrencyUnpaused(JUSD));
+			assert!(System::events().iter().any(|record| record.event == unpaused_event));
+			assert_ok!(Market::transfer(Some(ALICE).into(), BOB, JUSD, 10 * 1_000));
+		});
+}
+
+#[test]
+fn pause_native_currency_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::pause(Origin::root(), DNAR));
+			assert_noop!(
+				Market::transfer_native_currency(Some(ALICE).into(), BOB, 10),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_noop!(
+				Market::transfer(Some(ALICE).into(), BOB, DNAR, 10),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_noop!(
+				Market::update_balance(Origin::root(), ALICE, DNAR, -10),
+				Error::<Runtime>::CurrencyPaused
+			);
+		});
+}
+
+#[test]
+fn pause_all_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::pause(Origin::root(), JUSD));
+
+			assert_ok!(Market::pause_all(Origin::root()));
+			assert!(Market::market_paused());
+			assert!(System::events()
+				.iter()
+				.any(|record| record.event == Event::market(crate::Event::MarketPaused)));
+			assert_noop!(
+				Market::transfer(Some(ALICE).into(), BOB, SETT, 10 * 10_000),
+				Error::<Runtime>::MarketPaused
+			);
+			assert_noop!(
+				Market::transfer_native_currency(Some(ALICE).into(), BOB, 10),
+				Error::<Runtime>::MarketPaused
+			);
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::expand_supply(DNAR, SETT, 40 * 10_000, 4_000),
+				Error::<Runtime>::MarketPaused
+			);
+
+			assert_ok!(Market::unpause_all(Origin::root()));
+			assert!(!Market::market_paused());
+			assert!(System::events()
+				.iter()
+				.any(|record| record.event == Event::market(crate::Event::MarketUnpaused)));
+			assert_ok!(Market::transfer(Some(ALICE).into(), BOB, SETT, 10 * 10_000));
+			assert_noop!(
+				Market::transfer(Some(ALICE).into(), BOB, JUSD, 10 * 1_000),
+				Error::<Runtime>::CurrencyPaused
+			);
+		});
+}
+
+#[test]
+fn pause_fails_if_not_pause_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(Market::pause(Some(ALICE).into(), JUSD), BadOrigin);
+		assert_noop!(Market::unpause(Some(ALICE).into(), JUSD), BadOrigin);
+		assert_noop!(Market::pause_all(Some(ALICE).into()), BadOrigin);
+		assert_noop!(Market::unpause_all(Some(ALICE).into()), BadOrigin);
+	});
+}
+
+#[test]
+fn update_balance_call_should_work_with_council_origin() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::update_balance(Some(COUNCIL).into(), ALICE, DNAR, -10));
+			assert_eq!(Stp258Native::free_balance(&ALICE), 90);
+			assert_ok!(Market::update_balance(Some(COUNCIL).into(), ALICE, SETT, 10 * 10_000));
+			assert_eq!(Market::free_balance(SETT, &ALICE), 110 * 10_000);
+		});
+}
+
+#[test]
+fn stable_currency_registry_should_work_with_council_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Market::register_stable_currency(Some(COUNCIL).into(), 4, JUSD_INFO));
+		assert_ok!(Market::update_stable_currency(Some(COUNCIL).into(), 4, SETT_INFO));
+		assert_ok!(Market::set_supply_caps(Some(COUNCIL).into(), 4, None));
+		assert_ok!(Market::deregister_stable_currency(Some(COUNCIL).into(), 4));
+		assert_eq!(Market::stable_currencies(4), None);
+	});
+}
+
+#[test]
+fn supply_history_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_eq!(Market::supply_history(JUSD), vec![]);
+			assert_ok!(Market::update_balance(Origin::root(), SERPER, JUSD, 1_000 * 1_000));
+			assert_ok!(Stp258Serp::reserve(JUSD, &SERPER, 1_000 * 1_000));
+
+			System::set_block_number(1);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 100 * 1_000, 4_000));
+			System::set_block_number(2);
+			assert_ok!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 50 * 1_000, 3_000));
+
+			assert_eq!(
+				Market::supply_history(JUSD),
+				vec![
+					SupplyAdjustmentRecord {
+						block_number: 1,
+						adjustment: SupplyAdjustment::Expand(100 * 1_000),
+						quote_price: 4_000,
+						total_issuance: 1_500 * 1_000,
+					},
+					SupplyAdjustmentRecord {
+						block_number: 2,
+						adjustment: SupplyAdjustment::Contract(50 * 1_000),
+						quote_price: 3_000,
+						total_issuance: 1_450 * 1_000,
+					},
+				]
+			);
+			assert_eq!(Market::supply_history(SETT), vec![]);
+		});
+}
+
+#[test]
+fn supply_history_drops_oldest_records() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			for block_number in 1..=(MAX_SUPPLY_HISTORY as u64 + 2) {
+				System::set_block_number(block_number);
+				assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 1_000, 4_000));
+			}
+
+			let history = Market::supply_history(JUSD);
+			assert_eq!(history.len(), MAX_SUPPLY_HISTORY as usize);
+			assert_eq!(
+				history.iter().map(|record| record.block_number).collect::<Vec<_>>(),
+				vec![3, 4, 5]
+			);
+			assert_eq!(history.last().unwrap().total_issuance, 405 * 1_000);
+			assert_eq!(Market::supply_history_count(JUSD), MAX_SUPPLY_HISTORY + 2);
+		});
+}
+
+#[test]
+fn all_balances_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::reserve(SETT, &ALICE, 30 * 10_000));
+			assert_ok!(Market::reserve(DNAR, &ALICE, 40));
+
+			let mut balances = Market::all_balances(&ALICE);
+			balances.sort_by_key(|(currency_id, _)| *currency_id);
+			assert_eq!(
+				balances,
+				vec![
+					(
+						DNAR,
+						AccountBalance {
+							free: 60,
+							reserved: 40
+						}
+					),
+					(
+						SETT,
+						AccountBalance {
+							free: 70 * 10_000,
+							reserved: 30 * 10_000
+						}
+					),
+					(
+						JUSD,
+						AccountBalance {
+							free: 100 * 1_000,
+							reserved: 0
+						}
+					),
+				]
+			);
+		});
+}
+
+#[test]
+fn serp_up_and_serp_down_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Market::serp_up(Origin::root(), JUSD, 40 * 1_000, 4_000));
+			assert_eq!(Market::total_issuance(JUSD), 440 * 1_000);
+			let serped_up_event = Event::market(crate::Event::SerpedUpSupply(JUSD, 40 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == serped_up_event));
+
+			assert_ok!(Stp258Serp::reserve(JUSD, &SERPER, 50 * 1_000));
+			assert_ok!(Market::serp_down(Origin::root(), JUSD, 40 * 1_000, 4_000));
+			assert_eq!(Market::total_issuance(JUSD), 400 * 1_000);
+			let serped_down_event = Event::market(crate::Event::SerpedDownSupply(JUSD, 40 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == serped_down_event));
+		});
+}
+
+#[test]
+fn serp_up_and_serp_down_should_fail() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_noop!(Market::serp_up(Some(ALICE).into(), JUSD, 40 * 1_000, 4_000), BadOrigin);
+			assert_noop!(Market::serp_down(Some(ALICE).into(), JUSD, 40 * 1_000, 4_000), BadOrigin);
+			assert_noop!(
+				Market::serp_up(Origin::root(), DNAR, 40 * 1_000, 4_000),
+				Error::<Runtime>::NativeCurrencyCannotBeSerped
+			);
+			assert_noop!(
+				Market::serp_down(Origin::root(), 4, 40 * 1_000, 4_000),
+				Error::<Runtime>::StableCurrencyNotRegistered
+			);
+			assert_noop!(
+				Market::serp_up(Origin::root(), JUSD, JUSD_INFO.max_expansion + 1, 4_000),
+				Error::<Runtime>::ExceedsMaxExpansion
+			);
+		});
+}
+
+#[test]
+fn reservable_event_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Market::reserve(SETT, &ALICE, 30 * 10_000));
+			let reserved_event = Event::market(crate::Event::Reserved(SETT, ALICE, 30 * 10_000));
+			assert!(System::events().iter().any(|record| record.event == reserved_event));
+
+			assert_eq!(Market::unreserve(SETT, &ALICE, 40 * 10_000), 10 * 10_000);
+			let unreserved_event = Event::market(crate::Event::Unreserved(SETT, ALICE, 30 * 10_000));
+			assert!(System::events().iter().any(|record| record.event == unreserved_event));
+
+			assert_eq!(Market::slash(SETT, &ALICE, 110 * 10_000), 10 * 10_000);
+			let slashed_event = Event::market(crate::Event::Slashed(SETT, ALICE, 100 * 10_000));
+			assert!(System::events().iter().any(|record| record.event == slashed_event));
+
+			assert_ok!(Market::reserve(JUSD, &BOB, 50 * 1_000));
+			assert_eq!(Market::slash_reserved(JUSD, &BOB, 60 * 1_000), 10 * 1_000);
+			let reserve_slashed_event = Event::market(crate::Event::ReserveSlashed(JUSD, BOB, 50 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == reserve_slashed_event));
+
+			assert_ok!(Market::reserve(DNAR, &SERPER, 20));
+			assert_eq!(
+				Market::repatriate_reserved(DNAR, &SERPER, &BOB, 30, BalanceStatus::Free),
+				Ok(10)
+			);
+			assert_eq!(Stp258Native::free_balance(&BOB), 120);
+			let repatriated_event = Event::market(crate::Event::ReserveRepatriated(
+				DNAR,
+				SERPER,
+				BOB,
+				20,
+				BalanceStatus::Free,
+			));
+			assert!(System::events().iter().any(|record| record.event == repatriated_event));
+		});
+}
+
+#[test]
+fn reservable_event_skips_nothing_moved() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Market::reserve(SETT, &ALICE, 0));
+			assert_eq!(Market::unreserve(SETT, &ALICE, 10), 10);
+			assert_eq!(Market::slash_reserved(SETT, &ALICE, 10), 10);
+			assert!(System::events().is_empty());
+		});
+}
+
+#[test]
+fn lockable_event_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			const ID: LockIdentifier = *b"1       ";
+
+			assert_ok!(Market::set_lock(ID, DNAR, &ALICE, 50));
+			let lock_set_event = Event::market(crate::Event::LockSet(ID, DNAR, ALICE, 50));
+			assert!(System::events().iter().any(|record| record.event == lock_set_event));
+
+			assert_ok!(Market::extend_lock(ID, SETT, &ALICE, 60 * 10_000));
+			let lock_extended_event = Event::market(crate::Event::LockExtended(ID, SETT, ALICE, 60 * 10_000));
+			assert!(System::events().iter().any(|record| record.event == lock_extended_event));
+
+			// extending to less than the lock reports the lock kept
+			assert_ok!(Market::extend_lock(ID, DNAR, &ALICE, 30));
+			let lock_kept_event = Event::market(crate::Event::LockExtended(ID, DNAR, ALICE, 50));
+			assert!(System::events().iter().any(|record| record.event == lock_kept_event));
+			assert_eq!(Market::lock_amounts(&ALICE, (DNAR, ID)), 50);
+
+			assert_ok!(Market::remove_lock(ID, DNAR, &ALICE));
+			assert_eq!(Market::lock_amounts(&ALICE, (DNAR, ID)), 0);
+			let lock_removed_event = Event::market(crate::Event::LockRemoved(ID, DNAR, ALICE));
+			assert!(System::events().iter().any(|record| record.event == lock_removed_event));
+		});
+}
+
+#[test]
+fn transfer_all_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Market::transfer_all(Some(ALICE).into(), BOB, SETT));
+			assert_eq!(Market::free_balance(SETT, &ALICE), 0);
+			assert_eq!(Market::free_balance(SETT, &BOB), 200 * 10_000);
+			let transferred_event = Event::market(crate::Event::Transferred(SETT, ALICE, BOB, 100 * 10_000));
+			assert!(System::events().iter().any(|record| record.event == transferred_event));
+
+			// the locked balance is left behind
+			assert_ok!(Market::set_lock(*b"1       ", JUSD, &ALICE, 30 * 1_000));
+			assert_eq!(Market::transferable_balance(JUSD, &ALICE), 70 * 1_000);
+			assert_ok!(Market::transfer_all(Some(ALICE).into(), BOB, JUSD));
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 30 * 1_000);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 170 * 1_000);
+
+			// so is the balance locked outside the pallet
+			<PalletBalances as SetheumLockableCurrency<AccountId>>::set_lock(
+				*b"staking ",
+				&ALICE,
+				40,
+				WithdrawReasons::all(),
+			);
+			assert_eq!(Market::transferable_balance(DNAR, &ALICE), 60);
+			assert_ok!(Market::transfer_all(Some(ALICE).into(), BOB, DNAR));
+			assert_eq!(Stp258Native::free_balance(&ALICE), 40);
+			assert_eq!(Stp258Native::free_balance(&BOB), 160);
+		});
+}
+
+#[test]
+fn transfer_keep_alive_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::transfer_keep_alive(Some(ALICE).into(), BOB, SETT, 99 * 10_000));
+			assert_eq!(Market::free_balance(SETT, &ALICE), 10_000);
+			assert_noop!(
+				Market::transfer_keep_alive(Some(ALICE).into(), BOB, SETT, 10_000),
+				Error::<Runtime>::KeepAlive
+			);
+			assert_noop!(
+				Market::transfer_keep_alive(Some(ALICE).into(), BOB, SETT, 2 * 10_000),
+				Error::<Runtime>::BalanceTooLow
+			);
+
+			assert_ok!(Market::transfer_keep_alive(Some(ALICE).into(), BOB, DNAR, 99));
+			assert_eq!(Stp258Native::free_balance(&ALICE), 1);
+			assert_noop!(
+				Market::transfer_keep_alive(Some(ALICE).into(), BOB, DNAR, 1),
+				pallet_balances::Error::<Runtime>::KeepAlive
+			);
+		});
+}
+
+#[test]
+fn transfer_batch_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Market::transfer_batch(
+				Some(ALICE).into(),
+				vec![(BOB, SETT, 10 * 10_000), (SERPER, JUSD, 20 * 1_000), (BOB, DNAR, 30)]
+			));
+			assert_eq!(Market::free_balance(SETT, &ALICE), 90 * 10_000);
+			assert_eq!(Market::free_balance(SETT, &BOB), 110 * 10_000);
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 80 * 1_000);
+			assert_eq!(Market::free_balance(JUSD, &SERPER), 120 * 1_000);
+			assert_eq!(Stp258Native::free_balance(&ALICE), 70);
+			assert_eq!(Stp258Native::free_balance(&BOB), 130);
+
+			let transferred_event = Event::market(crate::Event::Transferred(JUSD, ALICE, SERPER, 20 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == transferred_event));
+		});
+}
+
+#[test]
+fn transfer_batch_is_all_or_nothing() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert!(Market::transfer_batch(
+				Some(ALICE).into(),
+				vec![(BOB, SETT, 10 * 10_000), (BOB, JUSD, 101 * 1_000)]
+			)
+			.is_err());
+			assert_eq!(Market::free_balance(SETT, &ALICE), 100 * 10_000);
+			assert_eq!(Market::free_balance(SETT, &BOB), 100 * 10_000);
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 100 * 1_000);
+		});
+}
+
+#[test]
+fn transfer_batch_fails_with_too_many_transfers() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			let transfers = vec![(BOB, SETT, 10_000); MAX_TRANSFER_BATCH_SIZE as usize + 1];
+			assert_noop!(
+				Market::transfer_batch(Some(ALICE).into(), transfers),
+				Error::<Runtime>::TooManyTransfers
+			);
+		});
+}
+
+#[test]
+fn merge_account_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::deposit(4, &ALICE, 50));
+
+			assert_ok!(Market::merge_account(Some(ALICE).into(), BOB));
+			assert_eq!(Stp258Native::total_balance(&ALICE), 0);
+			assert_eq!(Market::total_balance(SETT, &ALICE), 0);
+			assert_eq!(Market::total_balance(JUSD, &ALICE), 0);
+			assert_eq!(Market::free_balance(4, &ALICE), 0);
+			assert_eq!(Market::free_balance(4, &BOB), 50);
+			assert_eq!(Stp258Native::free_balance(&BOB), 200);
+			assert_eq!(Market::free_balance(SETT, &BOB), 200 * 10_000);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 200 * 1_000);
+
+			let merged_event = System::events()
+				.into_iter()
+				.find_map(|record| match record.event {
+					Event::market(crate::Event::AccountMerged(source, dest, mut moved)) => {
+						moved.sort();
+						Some((source, dest, moved))
+					}
+					_ => None,
+				})
+				.unwrap();
+			assert_eq!(
+				merged_event,
+				(ALICE, BOB, vec![(DNAR, 100), (SETT, 100 * 10_000), (JUSD, 100 * 1_000)])
+			);
+		});
+}
+
+#[test]
+fn merge_account_fails_with_locks_reserves_or_paused_currencies() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::set_lock(*b"1       ", SETT, &ALICE, 10));
+			assert_noop!(
+				Market::merge_account(Some(ALICE).into(), BOB),
+				Error::<Runtime>::AccountHasLocks
+			);
+			assert_ok!(Market::remove_lock(*b"1       ", SETT, &ALICE));
+
+			assert_ok!(Market::set_lock(*b"1       ", DNAR, &ALICE, 10));
+			assert_noop!(
+				Market::merge_account(Some(ALICE).into(), BOB),
+				Error::<Runtime>::AccountHasLocks
+			);
+			assert_ok!(Market::remove_lock(*b"1       ", DNAR, &ALICE));
+
+			assert_ok!(Market::reserve(JUSD, &ALICE, 10));
+			assert_noop!(
+				Market::merge_account(Some(ALICE).into(), BOB),
+				Error::<Runtime>::AccountHasReserves
+			);
+			assert_eq!(Market::unreserve(JUSD, &ALICE, 10), 0);
+
+			assert_ok!(Stp258Native::reserve(&ALICE, 10));
+			assert_noop!(
+				Market::merge_account(Some(ALICE).into(), BOB),
+				Error::<Runtime>::AccountHasReserves
+			);
+			assert_eq!(Stp258Native::unreserve(&ALICE, 10), 0);
+
+			assert_ok!(Market::pause(Origin::root(), SETT));
+			assert_noop!(
+				Market::merge_account(Some(ALICE).into(), BOB),
+				Error::<Runtime>::CurrencyPaused
+			);
+		});
+}
+
+#[test]
+fn named_reserves_should_be_independent() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::reserve_named(b"first   ", SETT, &ALICE, 30 * 10_000));
+			assert_ok!(Market::reserve_named(b"second  ", SETT, &ALICE, 20 * 10_000));
+			assert_eq!(Market::reserved_balance(SETT, &ALICE), 50 * 10_000);
+			assert_eq!(Market::reserved_balance_named(b"first   ", SETT, &ALICE), 30 * 10_000);
+
+			assert_eq!(Market::unreserve_named(b"first   ", SETT, &ALICE, 40 * 10_000), 10 * 10_000);
+			assert_eq!(Market::reserved_balance_named(b"first   ", SETT, &ALICE), 0);
+			assert_eq!(Market::reserved_balance_named(b"second  ", SETT, &ALICE), 20 * 10_000);
+			assert_eq!(Market::reserved_balance(SETT, &ALICE), 20 * 10_000);
+
+			assert_eq!(Market::slash_reserved_named(b"first   ", SETT, &ALICE, 10 * 10_000), 10 * 10_000);
+			assert_eq!(Market::slash_reserved_named(b"second  ", SETT, &ALICE, 5 * 10_000), 0);
+			assert_eq!(Market::reserved_balance_named(b"second  ", SETT, &ALICE), 15 * 10_000);
+			assert_eq!(Market::total_balance(SETT, &ALICE), 95 * 10_000);
+		});
+}
+
+#[test]
+fn named_reserves_should_work_for_native_currency() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Native::reserve_named(b"first   ", &ALICE, 40));
+			assert_ok!(Stp258Native::reserve(&ALICE, 10));
+			assert_eq!(Stp258Native::reserved_balance_named(b"first   ", &ALICE), 40);
+			assert_eq!(Stp258Native::reserved_balance(&ALICE), 50);
+
+			assert_eq!(Stp258Native::unreserve_named(b"second  ", &ALICE, 10), 10);
+			assert_eq!(Stp258Native::reserved_balance(&ALICE), 50);
+
+			assert_eq!(
+				Stp258Native::repatriate_reserved_named(b"first   ", &ALICE, &BOB, 10, BalanceStatus::Free),
+				Ok(0)
+			);
+			assert_eq!(Stp258Native::free_balance(&BOB), 110);
+			assert_eq!(
+				Stp258Native::repatriate_reserved_named(b"first   ", &ALICE, &BOB, 40, BalanceStatus::Reserved),
+				Ok(10)
+			);
+			assert_eq!(Stp258Native::reserved_balance_named(b"first   ", &ALICE), 0);
+			assert_eq!(Stp258Native::reserved_balance_named(b"first   ", &BOB), 30);
+			assert_eq!(Stp258Native::reserved_balance(&ALICE), 10);
+		});
+}
+
+#[test]
+fn unnamed_reserve_operations_should_leave_named_reserves() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::reserve_named(b"first   ", SETT, &ALICE, 30 * 10_000));
+			assert_ok!(Market::reserve(SETT, &ALICE, 10 * 10_000));
+
+			assert_eq!(Market::unreserve(SETT, &ALICE, 40 * 10_000), 30 * 10_000);
+			assert_eq!(Market::reserved_balance(SETT, &ALICE), 30 * 10_000);
+
+			assert_ok!(Market::reserve(SETT, &ALICE, 10 * 10_000));
+			assert_eq!(Market::slash_reserved(SETT, &ALICE, 20 * 10_000), 10 * 10_000);
+			assert_eq!(Market::reserved_balance(SETT, &ALICE), 30 * 10_000);
+
+			assert_eq!(
+				Market::repatriate_reserved(SETT, &ALICE, &BOB, 10 * 10_000, BalanceStatus::Free),
+				Ok(10 * 10_000)
+			);
+			assert_eq!(Market::reserved_balance_named(b"first   ", SETT, &ALICE), 30 * 10_000);
+			assert_eq!(Market::reserved_balance(SETT, &ALICE), 30 * 10_000);
+		});
+}
+
+#[test]
+fn merge_account_should_refuse_named_reserves() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Native::reserve_named(b"first   ", &ALICE, 10));
+			assert_noop!(
+				Market::merge_account(Some(ALICE).into(), BOB),
+				Error::<Runtime>::AccountHasReserves
+			);
+			assert_eq!(Stp258Native::reserved_balance_named(b"first   ", &ALICE), 10);
+		});
+}
+
+#[test]
+fn merge_account_should_refuse_open_orders() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Sell, 1_000, 10));
+			assert_noop!(
+				Market::merge_account(Some(ALICE).into(), BOB),
+				Error::<Runtime>::AccountHasOpenOrders
+			);
+			assert_eq!(Market::reserved_balance(DNAR, &ALICE), 10);
+
+			assert_ok!(Market::cancel_order(Some(ALICE).into(), JUSD, 0));
+			assert_ok!(Market::merge_account(Some(ALICE).into(), BOB));
+		});
+}
+
+#[test]
+fn lock_with_reasons_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::set_lock_with_reasons(
+				*b"1       ",
+				DNAR,
+				&ALICE,
+				100,
+				WithdrawReasons::except(WithdrawReasons::TRANSACTION_PAYMENT)
+			));
+			assert!(Market::withdraw(DNAR, &ALICE, 10).is_err());
+			assert_ok!(Market::ensure_can_withdraw_with_reasons(
+				DNAR,
+				&ALICE,
+				10,
+				WithdrawReasons::TRANSACTION_PAYMENT
+			));
+			assert_ok!(Market::withdraw_with_reasons(
+				DNAR,
+				&ALICE,
+				10,
+				WithdrawReasons::TRANSACTION_PAYMENT
+			));
+			assert_eq!(Market::free_balance(DNAR, &ALICE), 90);
+
+			assert_ok!(Market::set_lock(*b"1       ", DNAR, &ALICE, 50));
+			assert!(Market::withdraw_with_reasons(DNAR, &ALICE, 50, WithdrawReasons::TRANSACTION_PAYMENT).is_err());
+		});
+}
+
+#[test]
+fn reasons_fail_for_non_native_currency() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				Market::set_lock_with_reasons(*b"1       ", SETT, &ALICE, 10, WithdrawReasons::TRANSACTION_PAYMENT),
+				Error::<Runtime>::WithdrawReasonsNotSupported
+			);
+			assert_ok!(Market::set_lock_with_reasons(
+				*b"1       ",
+				SETT,
+				&ALICE,
+				10,
+				WithdrawReasons::all()
+			));
+			assert_noop!(
+				Market::withdraw_with_reasons(SETT, &ALICE, 10, WithdrawReasons::TRANSACTION_PAYMENT),
+				Error::<Runtime>::WithdrawReasonsNotSupported
+			);
+			assert_noop!(
+				Market::ensure_can_withdraw_with_reasons(SETT, &ALICE, 10, WithdrawReasons::TRANSFER),
+				Error::<Runtime>::WithdrawReasonsNotSupported
+			);
+			assert_ok!(Market::withdraw_with_reasons(SETT, &ALICE, 10, WithdrawReasons::all()));
+		});
+}
+
+#[test]
+fn timed_locks_should_be_lifted() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::set_lock_until(*b"1       ", DNAR, &ALICE, 50, 5));
+			assert_ok!(Stp258Native::set_lock_until(*b"1       ", &BOB, 50, 5));
+			assert_ok!(Market::set_lock_until(*b"2       ", SETT, &ALICE, 50 * 10_000, 6));
+			assert_eq!(Market::lock_expiry(*b"1       ", DNAR, &ALICE), Some(5));
+			assert!(Market::withdraw(DNAR, &ALICE, 60).is_err());
+			assert!(Market::withdraw(SETT, &ALICE, 60 * 10_000).is_err());
+
+			Market::on_initialize(5);
+			assert_eq!(Market::lock_expiry(*b"1       ", DNAR, &ALICE), None);
+			assert_ok!(Market::withdraw(DNAR, &ALICE, 60));
+			assert_ok!(Stp258Native::withdraw(&BOB, 60));
+			assert!(Market::withdraw(SETT, &ALICE, 60 * 10_000).is_err());
+
+			Market::on_initialize(6);
+			assert_ok!(Market::withdraw(SETT, &ALICE, 60 * 10_000));
+			let lock_removed_event = Event::market(crate::Event::LockRemoved(*b"2       ", SETT, ALICE));
+			assert!(System::events().iter().any(|record| record.event == lock_removed_event));
+		});
+}
+
+#[test]
+fn timed_locks_made_permanent_are_kept() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::set_lock_until(*b"1       ", DNAR, &ALICE, 50, 5));
+			assert_ok!(Market::set_lock(*b"1       ", DNAR, &ALICE, 50));
+			assert_eq!(Market::lock_expiry(*b"1       ", DNAR, &ALICE), None);
+
+			Market::on_initialize(5);
+			assert!(Market::withdraw(DNAR, &ALICE, 60).is_err());
+		});
+}
+
+#[test]
+fn set_lock_until_should_fail() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(5);
+			assert_noop!(
+				Market::set_lock_until(*b"1       ", DNAR, &ALICE, 50, 5),
+				Error::<Runtime>::LockExpiryInPast
+			);
+
+			assert_ok!(Market::set_lock_until(*b"1       ", DNAR, &ALICE, 50, 6));
+			assert_ok!(Market::set_lock_until(*b"1       ", DNAR, &BOB, 50, 6));
+			assert_ok!(Market::set_lock_until(*b"1       ", DNAR, &BOB, 40, 6));
+			assert_noop!(
+				Market::set_lock_until(*b"1       ", SETT, &ALICE, 50, 6),
+				Error::<Runtime>::TooManyLockExpiries
+			);
+		});
+}
+
+#[test]
+fn deposit_below_existential_deposit_should_fail() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(
+				Market::deposit(DNAR, &COUNCIL, 5),
+				Error::<Runtime>::BelowExistentialDeposit
+			);
+			assert_noop!(
+				Market::update_balance(Origin::root(), COUNCIL, DNAR, 5),
+				Error::<Runtime>::BelowExistentialDeposit
+			);
+			assert!(System::events().is_empty());
+
+			assert_ok!(Market::deposit(DNAR, &ALICE, 5));
+			assert_eq!(Stp258Native::free_balance(&ALICE), 105);
+			assert_ok!(Market::deposit(DNAR, &COUNCIL, 10));
+			assert_eq!(Stp258Native::free_balance(&COUNCIL), 10);
+			assert_eq!(Stp258Native::total_issuance(), 415);
+		});
+}
+
+#[test]
+fn vesting_schedules_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			let schedule = VestingSchedule {
+				start: 1,
+				cliff: 5,
+				duration: 10,
+				total: 20 * 10_000,
+			};
+			assert_ok!(Market::transfer(SETT, &ALICE, &BOB, 20 * 10_000));
+			assert_ok!(Market::add_vesting_schedule(&BOB, SETT, schedule));
+			assert_eq!(Market::vesting_schedules(&BOB, SETT), vec![schedule]);
+			assert_eq!(Market::free_balance(SETT, &BOB), 120 * 10_000);
+			assert!(Market::transfer(SETT, &BOB, &ALICE, 101 * 10_000).is_err());
+
+			System::set_block_number(5);
+			assert_ok!(Market::claim(Some(BOB).into(), SETT));
+			let claimed_event = Event::market(crate::Event::VestingClaimed(SETT, BOB, 20 * 10_000));
+			assert!(System::events().iter().any(|record| record.event == claimed_event));
+
+			System::set_block_number(6);
+			assert_ok!(Market::claim(Some(BOB).into(), SETT));
+			let claimed_event = Event::market(crate::Event::VestingClaimed(SETT, BOB, 10 * 10_000));
+			assert!(System::events().iter().any(|record| record.event == claimed_event));
+			assert!(Market::transfer(SETT, &BOB, &ALICE, 111 * 10_000).is_err());
+			assert_ok!(Market::transfer(SETT, &BOB, &ALICE, 110 * 10_000));
+
+			System::set_block_number(11);
+			assert_ok!(Market::claim(Some(BOB).into(), SETT));
+			assert!(Market::vesting_schedules(&BOB, SETT).is_empty());
+			assert_ok!(Market::transfer(SETT, &BOB, &ALICE, 10 * 10_000));
+		});
+}
+
+#[test]
+fn add_vesting_schedule_should_fail() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			let schedule = VestingSchedule {
+				start: 1,
+				cliff: 0,
+				duration: 10,
+				total: 10,
+			};
+			assert_noop!(
+				Market::add_vesting_schedule(&BOB, DNAR, VestingSchedule { cliff: 11, ..schedule }),
+				Error::<Runtime>::InvalidVestingSchedule
+			);
+			assert_noop!(
+				Market::add_vesting_schedule(&BOB, DNAR, VestingSchedule { total: 0, ..schedule }),
+				Error::<Runtime>::InvalidVestingSchedule
+			);
+
+			assert_ok!(Market::add_vesting_schedule(&BOB, DNAR, schedule));
+			assert_ok!(Market::add_vesting_schedule(&BOB, DNAR, schedule));
+			assert_noop!(
+				Market::add_vesting_schedule(&BOB, DNAR, schedule),
+				Error::<Runtime>::TooManyVestingSchedules
+			);
+		});
+}
+
+#[test]
+fn expansion_proceeds_should_vest() {
+	ExtBuilder::default()
+		.expansion_vesting(0, 10)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			let before = Market::free_balance(JUSD, &SERPER);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			let proceeds = Market::free_balance(JUSD, &SERPER) - before;
+			assert!(proceeds > 0);
+			assert_eq!(
+				Market::vesting_schedules(&SERPER, JUSD),
+				vec![VestingSchedule {
+					start: 1,
+					cliff: 0,
+					duration: 10,
+					total: proceeds,
+				}]
+			);
+			assert!(Market::transfer(JUSD, &SERPER, &ALICE, before + 1).is_err());
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			let newest = Market::vesting_schedules(&SERPER, JUSD)[1];
+
+			System::set_block_number(6);
+			let before = Market::free_balance(JUSD, &SERPER);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			let proceeds = Market::free_balance(JUSD, &SERPER) - before;
+			let schedules = Market::vesting_schedules(&SERPER, JUSD);
+			assert_eq!(schedules.len(), 2);
+			assert_eq!(
+				schedules[1],
+				VestingSchedule {
+					total: newest.total + proceeds,
+					..newest
+				}
+			);
+		});
+}
+
+#[test]
+fn expansion_proceeds_should_not_restart_the_cliff_once_slots_are_full() {
+	ExtBuilder::default()
+		.expansion_vesting(5, 10)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_eq!(Market::vesting_schedules(&SERPER, JUSD).len() as u32, MAX_VESTING_SCHEDULES);
+
+			System::set_block_number(5);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			let schedules = Market::vesting_schedules(&SERPER, JUSD);
+			assert_eq!(schedules.len() as u32, MAX_VESTING_SCHEDULES);
+			assert_eq!((schedules[1].start, schedules[1].cliff), (1, 5));
+
+			// The merged schedule starts vesting at the end of its original cliff.
+			assert!(schedules[1].locked_at(6) < schedules[1].total);
+		});
+}
+
+#[test]
+fn contraction_auction_should_work() {
+	ExtBuilder::default()
+		.auction_duration(10)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_eq!(
+				Market::auctions(JUSD),
+				Some(ContractionAuction {
+					start: 1,
+					end: 11,
+					target: 40 * 1_000,
+					raised: 0,
+					start_price: 6_000,
+					floor_price: 2_000,
+					quote_price: 4_000,
+				})
+			);
+			assert_eq!(Market::total_issuance(JUSD), 400 * 1_000);
+			assert!(Market::supply_adjustment_tallies(JUSD).buckets.is_empty());
+			assert_eq!(Market::auction_count(), 1);
+
+			assert_ok!(Market::bid(Some(ALICE).into(), JUSD, 30 * 1_000));
+			assert_ok!(Market::bid(Some(BOB).into(), JUSD, 20 * 1_000));
+			assert_eq!(Market::reserved_balance(JUSD, &BOB), 20 * 1_000);
+
+			Market::on_finalize(1);
+			assert_eq!(Market::auctions(JUSD).map(|auction| auction.raised), Some(36 * 1_000));
+			assert_eq!(Market::total_issuance(JUSD), 364 * 1_000);
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 70 * 1_000);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 94 * 1_000);
+			assert_eq!(Market::reserved_balance(JUSD, &BOB), 0);
+			assert_eq!(Stp258Native::free_balance(&ALICE), 105);
+			assert_eq!(Stp258Native::free_balance(&BOB), 101);
+			assert_eq!(
+				Market::supply_adjustment_tallies(JUSD).buckets,
+				vec![SupplyAdjustmentBucket {
+					block: 1,
+					expanded: 0,
+					contracted: 36 * 1_000,
+				}]
+			);
+
+			let settled_event = Event::market(crate::Event::BidSettled(JUSD, BOB, 6 * 1_000, 1));
+			assert!(System::events().iter().any(|record| record.event == settled_event));
+
+			System::set_block_number(11);
+			Market::on_finalize(11);
+			assert_eq!(Market::auctions(JUSD), None);
+			assert_eq!(Market::auction_count(), 0);
+			let closed_event = Event::market(crate::Event::AuctionClosed(JUSD, 36 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == closed_event));
+		});
+}
+
+#[test]
+fn contraction_auction_price_should_descend() {
+	ExtBuilder::default()
+		.auction_duration(10)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+
+			System::set_block_number(6);
+			assert_ok!(Market::bid(Some(ALICE).into(), JUSD, 8 * 1_000));
+			Market::on_finalize(6);
+			assert_eq!(Stp258Native::free_balance(&ALICE), 102);
+			assert_eq!(Market::auctions(JUSD).map(|auction| auction.raised), Some(8 * 1_000));
+
+			System::set_block_number(11);
+			assert_ok!(Market::bid(Some(BOB).into(), JUSD, 4 * 1_000));
+			Market::on_finalize(11);
+			assert_eq!(Stp258Native::free_balance(&BOB), 102);
+			assert_eq!(Market::auctions(JUSD), None);
+			assert_eq!(Market::total_issuance(JUSD), 388 * 1_000);
+
+			let closed_event = Event::market(crate::Event::AuctionClosed(JUSD, 12 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == closed_event));
+			assert_eq!(
+				Market::supply_history(JUSD).last().map(|record| record.adjustment),
+				Some(SupplyAdjustment::Contract(12 * 1_000))
+			);
+		});
+}
+
+#[test]
+fn contraction_auction_should_fail() {
+	ExtBuilder::default()
+		.auction_duration(10)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_noop!(
+				Market::bid(Some(ALICE).into(), JUSD, 10 * 1_000),
+				Error::<Runtime>::AuctionNotFound
+			);
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_noop!(
+				<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 40 * 1_000, 4_000),
+				Error::<Runtime>::AuctionInProgress
+			);
+
+			assert_ok!(Market::bid(Some(ALICE).into(), JUSD, 10 * 1_000));
+			assert_ok!(Market::bid(Some(BOB).into(), JUSD, 10 * 1_000));
+			assert_noop!(
+				Market::bid(Some(SERPER).into(), JUSD, 10 * 1_000),
+				Error::<Runtime>::TooManyBids
+			);
+		});
+}
+
+#[test]
+fn bonds_should_be_offered_and_redeemed() {
+	ExtBuilder::default()
+		.bond_duration(20)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 150 * 1_000, 4_000));
+			assert_eq!(Market::bond_offers(JUSD), 150 * 1_000);
+			assert_eq!(Market::total_issuance(JUSD), 400 * 1_000);
+			let offered_event = Event::market(crate::Event::BondsOffered(JUSD, 150 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == offered_event));
+
+			assert_ok!(Market::buy_bonds(Some(ALICE).into(), JUSD, 90 * 1_000));
+			assert_ok!(Market::buy_bonds(Some(BOB).into(), JUSD, 70 * 1_000));
+			assert_eq!(
+				Market::bonds(JUSD, 1),
+				Some(SerpBond {
+					owner: BOB,
+					face_value: 66 * 1_000,
+					expires: 21,
+				})
+			);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 40 * 1_000);
+			assert_eq!(Market::total_issuance(JUSD), 250 * 1_000);
+			assert_eq!(
+				Market::supply_history(JUSD).last().map(|record| (record.adjustment, record.quote_price)),
+				Some((SupplyAdjustment::Contract(60 * 1_000), 4_000))
+			);
+			assert_eq!(Market::bond_offer_quotes(JUSD), 0);
+			assert_noop!(
+				Market::buy_bonds(Some(ALICE).into(), JUSD, 10 * 1_000),
+				Error::<Runtime>::NoBondOffer
+			);
+
+			System::set_block_number(2);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 120 * 1_000, 4_000));
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 109 * 1_000);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 61 * 1_000);
+			assert_eq!(Market::total_issuance(JUSD), 370 * 1_000);
+			assert_eq!(Market::bonds(JUSD, 0), None);
+			assert_eq!(Market::bonds(JUSD, 1).map(|bond| bond.face_value), Some(45 * 1_000));
+			assert_eq!(Market::bond_queue(JUSD), (1, 2));
+			let redeemed_event = Event::market(crate::Event::BondRedeemed(JUSD, 1, BOB, 21 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == redeemed_event));
+		});
+}
+
+#[test]
+fn bonds_should_expire() {
+	ExtBuilder::default()
+		.bond_duration(5)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 150 * 1_000, 4_000));
+			assert_ok!(Market::buy_bonds(Some(ALICE).into(), JUSD, 10 * 1_000));
+
+			System::set_block_number(6);
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40 * 1_000, 4_000));
+			assert_eq!(Market::bonds(JUSD, 0), None);
+			assert_eq!(Market::bond_offers(JUSD), 0);
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 90 * 1_000);
+			assert_eq!(Market::total_issuance(JUSD), 430 * 1_000);
+			let expired_event = Event::market(crate::Event::BondExpired(JUSD, 0, ALICE, 11 * 1_000));
+			assert!(System::events().iter().any(|record| record.event == expired_event));
+		});
+}
+
+#[test]
+fn contract_supply_without_reserves_fails_without_bonds() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert!(<Market as SerpMarket<AccountId>>::contract_supply(DNAR, JUSD, 200 * 1_000, 4_000).is_err());
+			assert_eq!(Market::bond_offers(JUSD), 0);
+			assert_noop!(
+				Market::buy_bonds(Some(ALICE).into(), JUSD, 10 * 1_000),
+				Error::<Runtime>::NoBondOffer
+			);
+		});
+}
+
+#[test]
+fn set_seigniorage_shares_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let shares = SeigniorageShares {
+			treasury: Perbill::from_percent(10),
+			serpers: Perbill::from_percent(20),
+			stakers: Perbill::from_percent(30),
+			reserve_buffer: Perbill::from_percent(40),
+		};
+
+		assert_noop!(
+			Market::set_seigniorage_shares(Some(ALICE).into(), Some(shares)),
+			BadOrigin
+		);
+		assert_noop!(
+			Market::set_seigniorage_shares(
+				Origin::root(),
+				Some(SeigniorageShares {
+					reserve_buffer: Perbill::from_percent(30),
+					..shares
+				})
+			),
+			Error::<Runtime>::InvalidSeigniorageShares
+		);
+
+		assert_ok!(Market::set_seigniorage_shares(Some(COUNCIL).into(), Some(shares)));
+		assert_eq!(Market::seigniorage_shares(), Some(shares));
+		let updated_event = Event::market(crate::Event::SeigniorageSharesUpdated(Some(shares)));
+		assert!(System::events().iter().any(|record| record.event == updated_event));
+
+		assert_ok!(Market::set_seigniorage_shares(Origin::root(), None));
+		assert_eq!(Market::seigniorage_shares(), None);
+	});
+}
+
+#[test]
+fn seigniorage_should_be_distributed_on_expansion() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::set_seigniorage_shares(
+				Origin::root(),
+				Some(SeigniorageShares {
+					treasury: Perbill::from_percent(10),
+					serpers: Perbill::from_percent(20),
+					stakers: Perbill::from_percent(30),
+					reserve_buffer: Perbill::from_percent(40),
+				})
+			));
+
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 40_001, 4_000));
+			assert_eq!(Market::total_issuance(JUSD), 440_001);
+			assert_eq!(Market::free_balance(JUSD, &TREASURY), 4_000);
+			assert_eq!(Market::free_balance(JUSD, &SERPER), 108 * 1_000);
+			assert_eq!(Market::free_balance(JUSD, &STAKERS), 12_000);
+			assert_eq!(Market::free_balance(JUSD, &RESERVE_BUFFER), 16_001);
+			assert_eq!(Market::free_balance(JUSD, &SETTPAY), 100 * 1_000);
+
+			let paid_events = System::events()
+				.into_iter()
+				.filter(|record| matches!(record.event, Event::market(crate::Event::SeignioragePaid(..))))
+				.count();
+			assert_eq!(paid_events, 4);
+			let buffer_event = Event::market(crate::Event::SeignioragePaid(
+				JUSD,
+				SeigniorageRecipient::ReserveBuffer,
+				RESERVE_BUFFER,
+				16_001,
+			));
+			assert!(System::events().iter().any(|record| record.event == buffer_event));
+		});
+}
+
+#[test]
+fn seigniorage_dust_should_not_be_reported_as_issued() {
+	ExtBuilder::default()
+		.stable_existential_deposit(10 * 1_000)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::set_seigniorage_shares(
+				Origin::root(),
+				Some(SeigniorageShares {
+					treasury: Perbill::from_percent(10),
+					serpers: Perbill::from_percent(20),
+					stakers: Perbill::from_percent(30),
+					reserve_buffer: Perbill::from_percent(40),
+				})
+			));
+
+			// Only the serpers hold enough to take their share, the rest is dust.
+			assert_ok!(<Market as SerpMarket<AccountId>>::expand_supply(DNAR, JUSD, 2_000, 4_000));
+			assert_eq!(Market::total_issuance(JUSD), 400_400);
+			assert_eq!(Market::free_balance(JUSD, &SERPER), 100_400);
+			assert_eq!(Market::free_balance(JUSD, &RESERVE_BUFFER), 0);
+
+			let serped_up_event = Event::market(crate::Event::SerpedUpSupply(JUSD, 400));
+			assert!(System::events().iter().any(|record| record.event == serped_up_event));
+			assert_eq!(
+				Market::supply_history(JUSD).last().map(|record| record.adjustment),
+				Some(SupplyAdjustment::Expand(400))
+			);
+			assert_eq!(Market::supply_adjustment_tallies(JUSD).buckets[0].expanded, 400);
+		});
+}
+
+#[test]
+fn liquidity_should_be_added_and_removed() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_eq!(Market::spot_price(JUSD), None);
+			assert_noop!(
+				Market::add_liquidity(Some(ALICE).into(), DNAR, 50, 50_000, 0),
+				Error::<Runtime>::StableCurrencyNotRegistered
+			);
+
+			assert_ok!(Market::add_liquidity(Some(ALICE).into(), JUSD, 50, 50_000, 0));
+			assert_eq!(
+				Market::pools((DNAR, JUSD)),
+				LiquidityPool {
+					native_reserve: 50,
+					stable_reserve: 50_000,
+					total_shares: 50,
+				}
+			);
+			assert_eq!(Market::liquidity_shares((DNAR, JUSD), &ALICE), 50);
+			assert_eq!(Market::free_balance(DNAR, &Market::pool_account()), 50);
+			assert_eq!(Market::free_balance(JUSD, &Market::pool_account()), 50_000);
+
+			assert_noop!(
+				Market::add_liquidity(Some(BOB).into(), JUSD, 10, 10_000, 11),
+				Error::<Runtime>::SlippageExceeded
+			);
+			assert_noop!(
+				Market::add_liquidity(Some(BOB).into(), JUSD, 0, 0, 0),
+				Error::<Runtime>::InvalidLiquidityAmount
+			);
+			assert_ok!(Market::add_liquidity(Some(BOB).into(), JUSD, 20, 30_000, 0));
+			assert_eq!(Market::free_balance(DNAR, &BOB), 80);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 80_000);
+			assert_eq!(Market::liquidity_shares((DNAR, JUSD), &BOB), 20);
+			let added_event = Event::market(crate::Event::LiquidityAdded(BOB, JUSD, 20, 20_000, 20));
+			assert!(System::events().iter().any(|record| record.event == added_event));
+			assert_eq!(Market::spot_price(JUSD), Some(1_000));
+			assert_eq!(SpotPriceProvider::<Runtime>::get_price(JUSD), Some(1_000));
+
+			assert_noop!(
+				Market::remove_liquidity(Some(BOB).into(), JUSD, 30, 0, 0),
+				Error::<Runtime>::InsufficientShares
+			);
+			assert_noop!(
+				Market::remove_liquidity(Some(BOB).into(), JUSD, 20, 21, 0),
+				Error::<Runtime>::SlippageExceeded
+			);
+			assert_ok!(Market::remove_liquidity(Some(BOB).into(), JUSD, 20, 20, 20_000));
+			assert_eq!(Market::free_balance(DNAR, &BOB), 100);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 100_000);
+			assert_eq!(Market::liquidity_shares((DNAR, JUSD), &BOB), 0);
+			assert_eq!(
+				Market::pools((DNAR, JUSD)),
+				LiquidityPool {
+					native_reserve: 50,
+					stable_reserve: 50_000,
+					total_shares: 50,
+				}
+			);
+		});
+}
+
+#[test]
+fn swaps_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::add_liquidity(Some(ALICE).into(), JUSD, 50, 50_000, 0));
+
+			assert_noop!(
+				Market::swap_exact_in(Some(BOB).into(), JUSD, DNAR, 10_000, 9),
+				Error::<Runtime>::SlippageExceeded
+			);
+			assert_ok!(Market::swap_exact_in(Some(BOB).into(), JUSD, DNAR, 10_000, 8));
+			assert_eq!(Market::free_balance(DNAR, &BOB), 108);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 90_000);
+			let swapped_event = Event::market(crate::Event::Swapped(BOB, JUSD, DNAR, 10_000, 8));
+			assert!(System::events().iter().any(|record| record.event == swapped_event));
+
+			assert_noop!(
+				Market::swap_exact_out(Some(BOB).into(), DNAR, JUSD, 5_000, 4),
+				Error::<Runtime>::SlippageExceeded
+			);
+			assert_ok!(Market::swap_exact_out(Some(BOB).into(), DNAR, JUSD, 5_000, 5));
+			assert_eq!(Market::free_balance(DNAR, &BOB), 103);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 95_000);
+			assert_eq!(
+				Market::pools((DNAR, JUSD)),
+				LiquidityPool {
+					native_reserve: 47,
+					stable_reserve: 55_000,
+					total_shares: 50,
+				}
+			);
+		});
+}
+
+#[test]
+fn swaps_should_fail() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Market::add_liquidity(Some(ALICE).into(), JUSD, 50, 50_000, 0));
+
+			assert_noop!(
+				Market::swap_exact_in(Some(BOB).into(), JUSD, SETT, 1_000, 0),
+				Error::<Runtime>::InvalidSwapPair
+			);
+			assert_noop!(
+				Market::swap_exact_in(Some(BOB).into(), DNAR, DNAR, 10, 0),
+				Error::<Runtime>::InvalidSwapPair
+			);
+			assert_noop!(
+				Market::swap_exact_in(Some(BOB).into(), DNAR, SETT, 10, 0),
+				Error::<Runtime>::InsufficientLiquidity
+			);
+			assert_noop!(
+				Market::swap_exact_out(Some(BOB).into(), DNAR, JUSD, 50_000, 100),
+				Error::<Runtime>::InsufficientLiquidity
+			);
+		});
+}
+
+#[test]
+fn orders_should_match_in_price_time_priority() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Sell, 1_100, 10));
+			assert_ok!(Market::place_order(Some(SERPER).into(), JUSD, OrderSide::Sell, 1_000, 10));
+			assert_ok!(Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Sell, 1_000, 10));
+			assert_ok!(Market::place_order(Some(BOB).into(), JUSD, OrderSide::Buy, 1_200, 15));
+			assert_eq!(Market::reserved_balance(DNAR, &ALICE), 20);
+			assert_eq!(Market::reserved_balance(JUSD, &BOB), 18_000);
+			assert_eq!(Market::sell_orders(JUSD), vec![(1_000, 1), (1_000, 2), (1_100, 0)]);
+			assert_eq!(Market::buy_orders(JUSD), vec![(1_200, 3)]);
+			assert_eq!(Market::buy_order_book_count(), 1);
+
+			Market::on_finalize(1);
+			let first_match = Event::market(crate::Event::OrdersMatched(JUSD, 3, 1, 10, 1_000));
+			let second_match = Event::market(crate::Event::OrdersMatched(JUSD, 3, 2, 5, 1_000));
+			assert!(System::events().iter().any(|record| record.event == first_match));
+			assert!(System::events().iter().any(|record| record.event == second_match));
+
+			assert_eq!(Market::free_balance(DNAR, &BOB), 115);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 85_000);
+			assert_eq!(Market::reserved_balance(JUSD, &BOB), 0);
+			assert_eq!(Market::free_balance(DNAR, &SERPER), 90);
+			assert_eq!(Market::free_balance(JUSD, &SERPER), 110_000);
+			assert_eq!(Market::free_balance(DNAR, &ALICE), 80);
+			assert_eq!(Market::reserved_balance(DNAR, &ALICE), 15);
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 105_000);
+
+			assert_eq!(Market::buy_orders(JUSD), vec![]);
+			assert_eq!(Market::buy_order_book_count(), 0);
+			assert_eq!(Market::sell_orders(JUSD), vec![(1_000, 2), (1_100, 0)]);
+			assert_eq!(Market::orders(JUSD, 2).map(|order| order.amount), Some(5));
+			assert_eq!(Market::orders(JUSD, 3), None);
+			assert_eq!(Market::open_orders(&BOB), vec![]);
+			assert_eq!(Market::open_orders(&ALICE), vec![(JUSD, 0), (JUSD, 2)]);
+		});
+}
+
+#[test]
+fn orders_should_fill_at_the_older_order_price() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::place_order(Some(BOB).into(), JUSD, OrderSide::Buy, 1_000, 10));
+			Market::on_finalize(1);
+			assert_eq!(Market::buy_orders(JUSD), vec![(1_000, 0)]);
+
+			System::set_block_number(2);
+			assert_ok!(Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Sell, 900, 4));
+			Market::on_finalize(2);
+			let matched_event = Event::market(crate::Event::OrdersMatched(JUSD, 0, 1, 4, 1_000));
+			assert!(System::events().iter().any(|record| record.event == matched_event));
+
+			assert_eq!(Market::free_balance(DNAR, &ALICE), 96);
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 104_000);
+			assert_eq!(Market::free_balance(DNAR, &BOB), 104);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 90_000);
+			assert_eq!(Market::reserved_balance(JUSD, &BOB), 6_000);
+			assert_eq!(
+				Market::orders(JUSD, 0),
+				Some(Order {
+					owner: BOB,
+					side: OrderSide::Buy,
+					price: 1_000,
+					amount: 6,
+					reserved: 6_000,
+				})
+			);
+			assert_eq!(Market::sell_orders(JUSD), vec![]);
+		});
+}
+
+#[test]
+fn orders_missing_funds_should_be_cancelled() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Market::place_order(Some(BOB).into(), JUSD, OrderSide::Buy, 1_000, 10));
+			assert_ok!(Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Sell, 1_000, 10));
+			assert_eq!(Market::slash_reserved(JUSD, &BOB, 5_000), 0);
+
+			Market::on_finalize(1);
+			let cancelled_event = Event::market(crate::Event::OrderCancelled(JUSD, 0, BOB));
+			assert!(System::events().iter().any(|record| record.event == cancelled_event));
+			assert_eq!(Market::orders(JUSD, 0), None);
+			assert_eq!(Market::buy_orders(JUSD), vec![]);
+			assert_eq!(Market::sell_orders(JUSD), vec![(1_000, 1)]);
+			assert_eq!(Market::reserved_balance(DNAR, &ALICE), 10);
+			assert_eq!(Market::free_balance(JUSD, &BOB), 95_000);
+		});
+}
+
+#[test]
+fn orders_too_small_to_cost_anything_should_be_refused() {
+	ExtBuilder::default()
+		.existential_deposit(10)
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			// A native base unit is 10, so 2 at a price of 4 costs 0.8.
+			assert_noop!(
+				Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Sell, 4, 2),
+				Error::<Runtime>::InvalidOrder
+			);
+			assert_noop!(
+				Market::place_order(Some(BOB).into(), JUSD, OrderSide::Buy, 4, 2),
+				Error::<Runtime>::InvalidOrder
+			);
+
+			assert_ok!(Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Sell, 4, 30));
+			assert_ok!(Market::place_order(Some(BOB).into(), JUSD, OrderSide::Buy, 5, 2));
+			assert_eq!(Market::reserved_balance(JUSD, &BOB), 1);
+
+			// Filled at the older price of 4, the buy would get 2 for nothing.
+			Market::on_finalize(1);
+			let cancelled_event = Event::market(crate::Event::OrderCancelled(JUSD, 1, BOB));
+			assert!(System::events().iter().any(|record| record.event == cancelled_event));
+			assert_eq!(Market::orders(JUSD, 1), None);
+			assert_eq!(Market::buy_order_book_count(), 0);
+			assert_eq!(Market::reserved_balance(JUSD, &BOB), 0);
+			assert_eq!(Market::free_balance(DNAR, &BOB), 100);
+			assert_eq!(Market::sell_orders(JUSD), vec![(4, 0)]);
+			assert_eq!(Market::reserved_balance(DNAR, &ALICE), 30);
+		});
+}
+
+#[test]
+fn orders_should_be_limited_and_cancelled() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_noop!(
+				Market::place_order(Some(ALICE).into(), DNAR, OrderSide::Buy, 1_000, 1),
+				Error::<Runtime>::StableCurrencyNotRegistered
+			);
+			assert_noop!(
+				Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Buy, 0, 1),
+				Error::<Runtime>::InvalidOrder
+			);
+			assert_noop!(
+				Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Sell, 1_000, 0),
+				Error::<Runtime>::InvalidOrder
+			);
+
+			assert_ok!(Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Buy, 1_000, 1));
+			assert_ok!(Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Buy, 1_000, 1));
+			assert_noop!(
+				Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Buy, 1_000, 1),
+				Error::<Runtime>::TooManyOpenOrders
+			);
+			assert_eq!(Market::reserved_balance(JUSD, &ALICE), 2_000);
+
+			assert_noop!(
+				Market::cancel_order(Some(BOB).into(), JUSD, 0),
+				Error::<Runtime>::OrderNotFound
+			);
+			assert_ok!(Market::cancel_order(Some(ALICE).into(), JUSD, 0));
+			let cancelled_event = Event::market(crate::Event::OrderCancelled(JUSD, 0, ALICE));
+			assert!(System::events().iter().any(|record| record.event == cancelled_event));
+			assert_eq!(Market::reserved_balance(JUSD, &ALICE), 1_000);
+			assert_eq!(Market::free_balance(JUSD, &ALICE), 99_000);
+			assert_eq!(Market::buy_orders(JUSD), vec![(1_000, 1)]);
+			assert_eq!(Market::open_orders(&ALICE), vec![(JUSD, 1)]);
+			assert_noop!(
+				Market::cancel_order(Some(ALICE).into(), JUSD, 0),
+				Error::<Runtime>::OrderNotFound
+			);
+
+			assert_ok!(Market::place_order(Some(BOB).into(), JUSD, OrderSide::Buy, 1_000, 1));
+			assert_ok!(Market::place_order(Some(BOB).into(), JUSD, OrderSide::Buy, 1_000, 1));
+			assert_noop!(
+				Market::place_order(Some(SERPER).into(), JUSD, OrderSide::Buy, 1_000, 1),
+				Error::<Runtime>::OrderBookFull
+			);
+			assert_ok!(Market::place_order(Some(SERPER).into(), JUSD, OrderSide::Sell, 2_000, 1));
+
+			assert_ok!(Market::pause(Origin::root(), JUSD));
+			assert_noop!(
+				Market::place_order(Some(ALICE).into(), JUSD, OrderSide::Buy, 1_000, 1),
+				Error::<Runtime>::CurrencyPaused
+			);
+		});
+}